@@ -0,0 +1,188 @@
+//! Derive macros for structs whose fields are differences.
+//!
+//! The derived implementations operate field by field: addition adds corresponding fields, a struct
+//! is zero when each field is zero, and negation and multiplication apply to each field. Each derive
+//! requires the corresponding trait of every field, and adds these as bounds for generic structs.
+//!
+//! The generated code refers to `::differential_dataflow`, which must be a dependency of the crate
+//! using the derives.
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+#[macro_use]
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{Data, DeriveInput, Fields, Generics, Index, Member, Type};
+
+/// Derives `Semigroup` and `AddAssign<&Self>`, adding corresponding fields.
+#[proc_macro_derive(Semigroup)]
+pub fn derive_semigroup(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let (members, types) = match fields(&input) {
+        Ok(fields) => fields,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    let generics = add_bounds(&input.generics, &types, quote!(::differential_dataflow::difference::Semigroup));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // `AddAssign` is implemented for references with an additional lifetime.
+    let mut add_generics = generics.clone();
+    add_generics.params.insert(0, parse_quote!('__differential));
+    let (add_impl_generics, _, _) = add_generics.split_for_impl();
+
+    let is_zero = if members.is_empty() { quote!(true) } else { quote!(#(self.#members.is_zero())&&*) };
+
+    let expanded = quote! {
+        impl #impl_generics ::differential_dataflow::difference::Semigroup for #name #ty_generics #where_clause {
+            #[inline]
+            fn is_zero(&self) -> bool {
+                #[allow(unused_imports)]
+                use ::differential_dataflow::difference::Semigroup;
+                #is_zero
+            }
+        }
+
+        impl #add_impl_generics ::std::ops::AddAssign<&'__differential #name #ty_generics> for #name #ty_generics #where_clause {
+            #[inline]
+            fn add_assign(&mut self, rhs: &'__differential Self) {
+                #(self.#members += &rhs.#members;)*
+            }
+        }
+    };
+
+    expanded.into()
+}
+
+/// Derives `Monoid`, whose zero has each field zero.
+#[proc_macro_derive(Monoid)]
+pub fn derive_monoid(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let (members, types) = match fields(&input) {
+        Ok(fields) => fields,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    let generics = add_bounds(&input.generics, &types, quote!(::differential_dataflow::difference::Monoid));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let expanded = quote! {
+        impl #impl_generics ::differential_dataflow::difference::Monoid for #name #ty_generics #where_clause {
+            #[inline]
+            fn zero() -> Self {
+                #name { #(#members: ::differential_dataflow::difference::Monoid::zero(),)* }
+            }
+        }
+    };
+
+    expanded.into()
+}
+
+/// Derives `Neg`, negating each field, from which `Abelian` follows for monoids.
+#[proc_macro_derive(Abelian)]
+pub fn derive_abelian(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let (members, types) = match fields(&input) {
+        Ok(fields) => fields,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    let mut generics = input.generics.clone();
+    {
+        let where_clause = generics.make_where_clause();
+        for ty in types.iter() {
+            where_clause.predicates.push(parse_quote!(#ty: ::std::ops::Neg<Output=#ty>));
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let expanded = quote! {
+        impl #impl_generics ::std::ops::Neg for #name #ty_generics #where_clause {
+            type Output = Self;
+            #[inline]
+            fn neg(self) -> Self {
+                #name { #(#members: -self.#members,)* }
+            }
+        }
+    };
+
+    expanded.into()
+}
+
+/// Derives `Mul<T>` for each `T: Copy` that multiplies every field without changing its type.
+#[proc_macro_derive(Mul)]
+pub fn derive_mul(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let (members, types) = match fields(&input) {
+        Ok(fields) => fields,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    let mut generics = input.generics.clone();
+    generics.params.push(parse_quote!(__DifferentialMul: Copy));
+    {
+        let where_clause = generics.make_where_clause();
+        for ty in types.iter() {
+            where_clause.predicates.push(parse_quote!(#ty: ::std::ops::Mul<__DifferentialMul, Output=#ty>));
+        }
+    }
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    let expanded = quote! {
+        impl #impl_generics ::std::ops::Mul<__DifferentialMul> for #name #ty_generics #where_clause {
+            type Output = Self;
+            #[inline]
+            fn mul(self, other: __DifferentialMul) -> Self {
+                #name { #(#members: self.#members * other,)* }
+            }
+        }
+    };
+
+    expanded.into()
+}
+
+/// The members and types of the fields of a struct, or an error for enums and unions.
+fn fields(input: &DeriveInput) -> Result<(Vec<Member>, Vec<Type>), syn::Error> {
+    match &input.data {
+        Data::Struct(data) => {
+            let fields = match &data.fields {
+                Fields::Named(fields) => fields.named.iter().collect::<Vec<_>>(),
+                Fields::Unnamed(fields) => fields.unnamed.iter().collect::<Vec<_>>(),
+                Fields::Unit => Vec::new(),
+            };
+            let members =
+            fields
+                .iter()
+                .enumerate()
+                .map(|(index, field)| match &field.ident {
+                    Some(ident) => Member::Named(ident.clone()),
+                    None => Member::Unnamed(Index::from(index)),
+                })
+                .collect();
+            let types = fields.iter().map(|field| field.ty.clone()).collect();
+            Ok((members, types))
+        },
+        _ => Err(syn::Error::new_spanned(&input.ident, "differences can only be derived for structs")),
+    }
+}
+
+/// Adds the bound `field: bound` to the where clause, for each field type.
+fn add_bounds(generics: &Generics, types: &[Type], bound: TokenStream2) -> Generics {
+    let mut generics = generics.clone();
+    {
+        let where_clause = generics.make_where_clause();
+        for ty in types.iter() {
+            where_clause.predicates.push(parse_quote!(#ty: #bound));
+        }
+    }
+    generics
+}
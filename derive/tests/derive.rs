@@ -0,0 +1,68 @@
+#[macro_use]
+extern crate abomonation_derive;
+extern crate abomonation;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate differential_derive;
+extern crate differential_dataflow;
+extern crate timely;
+
+use timely::dataflow::operators::Capture;
+use timely::dataflow::operators::capture::Extract;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::operators::CountTotal;
+use differential_dataflow::difference::{Semigroup, Monoid, DiffPair};
+
+#[derive(Abomonation, Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Semigroup, Monoid, Abelian, Mul)]
+struct Aggregates {
+    revenue: isize,
+    quantity: isize,
+    count: isize,
+}
+
+#[derive(Abomonation, Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Semigroup, Monoid, Abelian, Mul)]
+struct Nested<R>(R, DiffPair<R, R>);
+
+#[test]
+fn derived_arithmetic() {
+
+    let mut total = Aggregates::zero();
+    assert!(total.is_zero());
+
+    total += &Aggregates { revenue: 10, quantity: 2, count: 1 };
+    total += &(Aggregates { revenue: 5, quantity: 1, count: 1 } * 3);
+    assert_eq!(total, Aggregates { revenue: 25, quantity: 5, count: 4 });
+
+    let negated = -total;
+    total += &negated;
+    assert!(total.is_zero());
+
+    let mut nested = Nested(1i64, DiffPair::new(2, 3));
+    nested += &Nested(-1, DiffPair::new(-2, 0));
+    assert!(!nested.is_zero());
+    assert_eq!(-nested * 2, Nested(0, DiffPair::new(0, -6)));
+}
+
+#[test]
+fn derived_accumulation() {
+
+    let data = timely::example(|scope| {
+        scope
+            .new_collection_from(vec![("a", 10, 2), ("b", 5, 1), ("a", 3, 4)]).1
+            .explode(|(name, revenue, quantity)| Some((name, Aggregates { revenue, quantity, count: 1 })))
+            .count_total()
+            .inner
+            .capture()
+    });
+
+    let mut results = data.extract().into_iter().flat_map(|(_, data)| data).map(|(x, _, _)| x).collect::<Vec<_>>();
+    results.sort();
+
+    assert_eq!(results, vec![
+        ("a", Aggregates { revenue: 13, quantity: 6, count: 2 }),
+        ("b", Aggregates { revenue: 5, quantity: 1, count: 1 }),
+    ]);
+}
@@ -0,0 +1,73 @@
+extern crate timely;
+extern crate graph_map;
+extern crate differential_dataflow;
+
+extern crate dogsdogsdogs;
+
+use timely::dataflow::operators::probe::Handle;
+use differential_dataflow::input::Input;
+use graph_map::GraphMMap;
+
+use dogsdogsdogs::plan::Query;
+
+fn main() {
+
+    // snag a filename to use for the input graph.
+    let filename = std::env::args().nth(1).unwrap();
+    let batching = std::env::args().nth(2).unwrap().parse::<usize>().unwrap();
+    let inspect = std::env::args().any(|x| x == "inspect");
+
+    timely::execute_from_args(std::env::args().skip(2), move |worker| {
+
+        let timer = std::time::Instant::now();
+        let graph = GraphMMap::new(&filename);
+
+        let peers = worker.peers();
+        let index = worker.index();
+
+        let mut probe = Handle::new();
+
+        let mut input = worker.dataflow::<usize,_,_>(|scope| {
+
+            let (edges_input, edges) = scope.new_collection();
+
+            // Q(a,b,c,d) := E(a,b), E(a,c), E(a,d), E(b,c), E(b,d), E(c,d)
+            let cliques =
+            Query::new()
+                .relation("edges", &edges.map(|(x,y): (u32, u32)| vec![x, y]))
+                .atom("edges", &[0, 1])
+                .atom("edges", &[0, 2])
+                .atom("edges", &[0, 3])
+                .atom("edges", &[1, 2])
+                .atom("edges", &[1, 3])
+                .atom("edges", &[2, 3])
+                .bindings()
+                .expect("malformed query");
+
+            cliques
+                .filter(move |_| inspect)
+                .inspect(|x| println!("\t4-clique: {:?}", x))
+                .probe_with(&mut probe);
+
+            edges_input
+        });
+
+        let mut index = index;
+        while index < graph.nodes() {
+            input.advance_to(index);
+            for &edge in graph.edges(index) {
+                input.insert((index as u32, edge));
+            }
+            index += peers;
+            input.advance_to(index);
+            input.flush();
+            if (index / peers) % batching == 0 {
+                while probe.less_than(input.time()) {
+                    worker.step();
+                }
+                println!("{:?}\tRound {} complete", timer.elapsed(), index);
+            }
+        }
+
+    }).unwrap();
+}
@@ -25,6 +25,7 @@ use differential_dataflow::operators::arrange::{ArrangeBySelf, ArrangeByKey};
 
 pub mod altneu;
 pub mod operators;
+pub mod plan;
 
 /// A type capable of extending a stream of prefixes.
 ///
@@ -0,0 +1,296 @@
+//! Planning worst-case optimal delta queries from conjunctive queries.
+//!
+//! A conjunctive query binds the columns of named relations to variables, as a list of atoms, and
+//! its bindings are the assignments of values to variables consistent with a row of each atom's
+//! relation. The planner maintains the bindings with one delta query for each atom: changes to the
+//! atom's relation bind its variables, and each remaining variable is bound in turn by `extend`, using
+//! a `CollectionExtender` for each atom in which the variable appears.
+//!
+//! Other atoms whose variables are all among the atom's own variables bind nothing new, and instead
+//! validate the atom's changes before any variable is extended.
+//!
+//! The atoms are ordered, and the delta query for an atom sees changes to earlier atoms at the same
+//! time and changes to later atoms only at strictly earlier times, using `AltNeu` timestamps. Several
+//! atoms may use the same relation, and they share its indices.
+//!
+//! Relations are treated as sets: the planner projects relations onto the columns each extender needs,
+//! and makes these projections distinct, even when no columns are discarded. The changes to each atom's
+//! own relation are not made distinct, and so a relation with repeated rows gives bindings with the
+//! multiplicities of those rows.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::Mul;
+
+use timely::dataflow::Scope;
+
+use differential_dataflow::{ExchangeData, Collection};
+use differential_dataflow::difference::{Monoid, Abelian};
+use differential_dataflow::lattice::Lattice;
+use differential_dataflow::operators::Threshold;
+use differential_dataflow::operators::delta::{distinct, position};
+
+use {CollectionIndex, PrefixExtender, ProposeExtensionMethod, ValidateExtensionMethod};
+use altneu::AltNeu;
+
+/// A conjunctive query over named relations, whose bindings are maintained by delta queries.
+///
+/// Variables are identified by integers, and each binding lists the values of the variables in order.
+pub struct Query<G: Scope, V: ExchangeData, R: Monoid> {
+    relations: HashMap<String, Collection<G, Vec<V>, R>>,
+    atoms: Vec<(String, Vec<usize>)>,
+}
+
+impl<G, V, R> Query<G, V, R>
+where
+    G: Scope,
+    G::Timestamp: Lattice+ExchangeData,
+    V: ExchangeData+Hash+Default,
+    R: ExchangeData+Abelian+Mul<Output=R>+From<i8>,
+{
+    /// Creates a query with no relations or atoms.
+    pub fn new() -> Self {
+        Query {
+            relations: HashMap::new(),
+            atoms: Vec::new(),
+        }
+    }
+
+    /// Names a relation, so that atoms may use it.
+    pub fn relation(&mut self, name: &str, collection: &Collection<G, Vec<V>, R>) -> &mut Self {
+        self.relations.insert(name.to_owned(), collection.clone());
+        self
+    }
+
+    /// Adds an atom binding each column of the named relation to the corresponding variable.
+    ///
+    /// The same variable may bind several columns, which restricts the relation to rows whose values
+    /// in those columns are equal.
+    pub fn atom(&mut self, name: &str, variables: &[usize]) -> &mut Self {
+        self.atoms.push((name.to_owned(), variables.to_vec()));
+        self
+    }
+
+    /// The order in which variables are bound by the delta query for each atom.
+    ///
+    /// Each order starts with the atom's variables, and then repeatedly binds the variable appearing in
+    /// the most atoms that already have some bound variable, preferring smaller variables among ties.
+    /// An error is returned if the query has no atoms, if an atom names an undefined relation or binds
+    /// no variables, or if some variable is bound by no atom.
+    pub fn plan(&self) -> Result<Vec<Vec<usize>>, String> {
+
+        if self.atoms.is_empty() {
+            return Err("a query must have at least one atom".to_owned());
+        }
+        for &(ref name, _) in self.atoms.iter() {
+            if !self.relations.contains_key(name) {
+                return Err(format!("atom uses undefined relation {:?}", name));
+            }
+        }
+        for &(ref name, ref variables) in self.atoms.iter() {
+            if variables.is_empty() {
+                return Err(format!("atom using relation {:?} binds no variables", name));
+            }
+        }
+
+        let variables = self.variables();
+        for variable in 0 .. variables {
+            if !self.atoms.iter().any(|x| x.1.contains(&variable)) {
+                return Err(format!("variable {} is bound by no atom", variable));
+            }
+        }
+
+        let mut orders = Vec::new();
+        for atom in self.atoms.iter() {
+            let mut order = distinct(&atom.1);
+            while order.len() < variables {
+                let next =
+                (0 .. variables)
+                    .filter(|v| !order.contains(v))
+                    .max_by_key(|&v| {
+                        let connected =
+                        self.atoms
+                            .iter()
+                            .filter(|a| a.1.contains(&v) && a.1.iter().any(|x| order.contains(x)))
+                            .count();
+                        (connected, ::std::cmp::Reverse(v))
+                    })
+                    .expect("unbound variables remain");
+                order.push(next);
+            }
+            orders.push(order);
+        }
+        Ok(orders)
+    }
+
+    /// Forms the bindings of the query, as the sum of the delta queries for each atom.
+    pub fn bindings(&self) -> Result<Collection<G, Vec<V>, R>, String> {
+
+        let orders = self.plan()?;
+        let variables = self.variables();
+
+        // Projections of relations onto key columns and one value column, shared by all atoms.
+        let mut projections = HashMap::new();
+        for (delta, order) in orders.iter().enumerate() {
+            for other in self.contained(delta) {
+                let atom = &self.atoms[other];
+                let (keys, value) = checked_columns(&atom.1);
+                let relation = &self.relations[&atom.0];
+                projections
+                    .entry((atom.0.clone(), pattern(&atom.1), keys.clone(), value))
+                    .or_insert_with(|| project(relation, &atom.1, keys, value));
+            }
+            for bound in distinct(&self.atoms[delta].1).len() .. order.len() {
+                let variable = order[bound];
+                for atom in self.atoms.iter().filter(|a| a.1.contains(&variable)) {
+                    let (keys, value) = columns(&atom.1, &order[.. bound], variable);
+                    let relation = &self.relations[&atom.0];
+                    projections
+                        .entry((atom.0.clone(), pattern(&atom.1), keys.clone(), value))
+                        .or_insert_with(|| project(relation, &atom.1, keys, value));
+                }
+            }
+        }
+
+        let mut scope = self.relations[&self.atoms[0].0].scope();
+        let bindings = scope.scoped::<AltNeu<G::Timestamp>,_,_>("WorstCaseOptimalJoin", |inner| {
+
+            // Indices of projections, with later atoms visible only at strictly earlier times.
+            let mut indices = HashMap::new();
+            let mut index_for = |atom: &(String, Vec<usize>), keys: &Vec<usize>, value: usize, neu: bool| {
+                indices
+                    .entry((atom.0.clone(), pattern(&atom.1), keys.clone(), value, neu))
+                    .or_insert_with(|| {
+                        let projection: &Collection<G, (Vec<V>, V), R> = &projections[&(atom.0.clone(), pattern(&atom.1), keys.clone(), value)];
+                        let entered = projection.enter(inner);
+                        if neu { CollectionIndex::index(&entered.delay(|time| AltNeu::neu(time.time.clone()))) }
+                        else { CollectionIndex::index(&entered) }
+                    })
+                    .clone()
+            };
+
+            let mut results = Vec::new();
+
+            for (delta, order) in orders.iter().enumerate() {
+
+                // Changes to the atom's relation bind its distinct variables, in order.
+                let atom_variables = self.atoms[delta].1.clone();
+                let first = distinct(&atom_variables).into_iter().map(|v| position(&atom_variables, v)).collect::<Vec<_>>();
+                let mut prefixes =
+                self.relations[&self.atoms[delta].0]
+                    .enter(inner)
+                    .filter(move |row| (0 .. atom_variables.len()).all(|c| row[c] == row[position(&atom_variables, atom_variables[c])]))
+                    .map(move |row| first.iter().map(|&c| row[c].clone()).collect::<Vec<_>>());
+
+                // Other atoms whose variables are already bound restrict the changes, as validators.
+                let own = distinct(&self.atoms[delta].1);
+                for other in self.contained(delta) {
+                    let atom = &self.atoms[other];
+                    let (keys, value) = checked_columns(&atom.1);
+                    let index = index_for(atom, &keys, value, other > delta);
+                    let positions = keys.iter().map(|&c| position(&own, atom.1[c])).collect::<Vec<_>>();
+                    let checked = position(&own, atom.1[value]);
+                    prefixes =
+                    prefixes
+                        .map(move |prefix| { let value = prefix[checked].clone(); (prefix, value) })
+                        .validate_using(&mut index.extend_using(move |prefix: &Vec<V>| positions.iter().map(|&p| prefix[p].clone()).collect::<Vec<_>>()))
+                        .map(|(prefix, _value)| prefix);
+                }
+
+                for bound in distinct(&self.atoms[delta].1).len() .. order.len() {
+
+                    let variable = order[bound];
+                    let mut extenders = Vec::new();
+                    for (other, atom) in self.atoms.iter().enumerate().filter(|&(_, a)| a.1.contains(&variable)) {
+                        let (keys, value) = columns(&atom.1, &order[.. bound], variable);
+                        let index = index_for(atom, &keys, value, other > delta);
+                        let positions = keys.iter().map(|&c| position(&order[.. bound], atom.1[c])).collect::<Vec<_>>();
+                        extenders.push(index.extend_using(move |prefix: &Vec<V>| positions.iter().map(|&p| prefix[p].clone()).collect::<Vec<_>>()));
+                    }
+
+                    let mut extenders = extenders.iter_mut().map(|x| x as &mut PrefixExtender<_, R, Prefix=Vec<V>, Extension=V>).collect::<Vec<_>>();
+                    prefixes =
+                    prefixes
+                        .extend(&mut extenders[..])
+                        .map(|(mut prefix, value)| { prefix.push(value); prefix });
+                }
+
+                // Rearrange bindings from the order of binding into the order of variables.
+                let output = (0 .. variables).map(|v| position(order, v)).collect::<Vec<_>>();
+                results.push(prefixes.map(move |prefix| output.iter().map(|&p| prefix[p].clone()).collect::<Vec<_>>()).leave());
+            }
+
+            let mut bindings = results.pop().expect("at least one atom");
+            for other in results.iter() {
+                bindings = bindings.concat(other);
+            }
+            bindings
+        });
+
+        Ok(bindings)
+    }
+
+    /// The atoms other than `delta` whose variables are all variables of `delta`.
+    fn contained(&self, delta: usize) -> Vec<usize> {
+        let variables = &self.atoms[delta].1;
+        (0 .. self.atoms.len())
+            .filter(|&other| other != delta && self.atoms[other].1.iter().all(|v| variables.contains(v)))
+            .collect()
+    }
+
+    /// The number of variables, one more than the largest variable used.
+    fn variables(&self) -> usize {
+        self.atoms.iter().flat_map(|x| x.1.iter().cloned()).max().map(|x| x + 1).unwrap_or(0)
+    }
+}
+
+impl<G, V, R> Default for Query<G, V, R>
+where
+    G: Scope,
+    G::Timestamp: Lattice+ExchangeData,
+    V: ExchangeData+Hash+Default,
+    R: ExchangeData+Abelian+Mul<Output=R>+From<i8>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The key columns of an atom for the bound variables, and the column of `variable`.
+///
+/// Each distinct bound variable contributes the column of its first occurrence.
+fn columns(atom: &[usize], bound: &[usize], variable: usize) -> (Vec<usize>, usize) {
+    let keys = distinct(atom).into_iter().filter(|v| bound.contains(v)).map(|v| position(atom, v)).collect();
+    (keys, position(atom, variable))
+}
+
+/// The key columns and value column with which to check an atom whose variables are all bound.
+///
+/// The value is the column of the last distinct variable, and the keys those of the others.
+fn checked_columns(atom: &[usize]) -> (Vec<usize>, usize) {
+    let variables = distinct(atom);
+    let (&last, others) = variables.split_last().expect("atoms bind variables");
+    columns(atom, others, last)
+}
+
+/// Projects the rows of `relation` consistent with repeated variables onto key columns and a value column.
+fn project<G, V, R>(relation: &Collection<G, Vec<V>, R>, atom: &[usize], keys: Vec<usize>, value: usize) -> Collection<G, (Vec<V>, V), R>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    V: ExchangeData+Hash,
+    R: ExchangeData+Abelian+From<i8>,
+{
+    let atom = atom.to_vec();
+    relation
+        .filter(move |row| (0 .. atom.len()).all(|c| row[c] == row[position(&atom, atom[c])]))
+        .map(move |row| (keys.iter().map(|&c| row[c].clone()).collect::<Vec<_>>(), row[value].clone()))
+        .distinct_core()
+}
+
+/// For each column of an atom, the first column bound to the same variable.
+///
+/// Atoms using the same relation with the same pattern restrict it to the same rows.
+fn pattern(atom: &[usize]) -> Vec<usize> {
+    atom.iter().map(|&v| position(atom, v)).collect()
+}
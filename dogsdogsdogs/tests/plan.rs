@@ -0,0 +1,179 @@
+extern crate timely;
+extern crate differential_dataflow;
+extern crate dogsdogsdogs;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::operators::{Join, Threshold};
+
+use dogsdogsdogs::plan::Query;
+
+#[test]
+fn query_triangles() {
+
+    timely::execute(timely::Configuration::Thread, move |worker| {
+
+        let mut input = worker.dataflow::<u32,_,_>(|scope| {
+
+            let (input, edges) = scope.new_collection::<(u32, u32), isize>();
+            let edges = edges.distinct();
+            let rows = edges.map(|(x, y)| vec![x, y]);
+
+            // triangles(a,b,c) := edges(a,b), edges(b,c), edges(a,c)
+            let bindings =
+            Query::new()
+                .relation("edges", &rows)
+                .atom("edges", &[0, 1])
+                .atom("edges", &[1, 2])
+                .atom("edges", &[0, 2])
+                .bindings()
+                .expect("well-formed query");
+
+            let expected =
+            edges.map(|(a, b)| (b, a))
+                 .join_map(&edges, |&b, &a, &c| ((a, c), b))
+                 .semijoin(&edges)
+                 .map(|((a, c), b)| vec![a, b, c]);
+
+            bindings.assert_eq(&expected);
+            input
+        });
+
+        // several edges change at once, so that changes to different atoms coincide.
+        input.insert((0, 1));
+        input.insert((1, 2));
+        input.insert((0, 2));
+        input.insert((2, 3));
+        input.advance_to(1);
+        input.insert((1, 3));
+        input.insert((0, 3));
+        input.insert((0, 3));
+        input.advance_to(2);
+        input.remove((1, 2));
+        input.insert((2, 1));
+        input.advance_to(3);
+        input.remove((0, 1));
+        input.insert((1, 0));
+    }).unwrap();
+}
+
+#[test]
+fn query_repeated_variables() {
+
+    timely::execute(timely::Configuration::Thread, move |worker| {
+
+        let mut input = worker.dataflow::<u32,_,_>(|scope| {
+
+            let (input, edges) = scope.new_collection::<(u32, u32), isize>();
+            let rows = edges.map(|(x, y)| vec![x, y]);
+
+            // loops(a,b) := edges(a,a), edges(a,b)
+            let bindings =
+            Query::new()
+                .relation("edges", &rows)
+                .atom("edges", &[0, 0])
+                .atom("edges", &[0, 1])
+                .bindings()
+                .expect("well-formed query");
+
+            let expected =
+            edges.filter(|&(x, y)| x == y)
+                 .map(|(a, _)| (a, ()))
+                 .join_map(&edges, |&a, &(), &b| vec![a, b]);
+
+            bindings.assert_eq(&expected);
+            input
+        });
+
+        input.insert((0, 0));
+        input.insert((0, 1));
+        input.insert((1, 2));
+        input.advance_to(1);
+        input.insert((1, 1));
+        input.remove((0, 0));
+    }).unwrap();
+}
+
+#[test]
+fn query_symmetric_edges() {
+
+    timely::execute(timely::Configuration::Thread, move |worker| {
+
+        let mut input = worker.dataflow::<u32,_,_>(|scope| {
+
+            let (input, edges) = scope.new_collection::<(u32, u32), isize>();
+            let edges = edges.distinct();
+            let rows = edges.map(|(x, y)| vec![x, y]);
+
+            // mutual(a,b) := edges(a,b), edges(b,a)
+            let bindings =
+            Query::new()
+                .relation("edges", &rows)
+                .atom("edges", &[0, 1])
+                .atom("edges", &[1, 0])
+                .bindings()
+                .expect("well-formed query");
+
+            let expected =
+            edges.map(|edge| (edge, ()))
+                 .semijoin(&edges.map(|(x, y)| (y, x)))
+                 .map(|((a, b), ())| vec![a, b]);
+
+            bindings.assert_eq(&expected);
+            input
+        });
+
+        // edges gain and lose their reverses, sometimes at the same time.
+        input.insert((0, 1));
+        input.insert((1, 2));
+        input.insert((2, 1));
+        input.insert((3, 3));
+        input.advance_to(1);
+        input.insert((1, 0));
+        input.remove((2, 1));
+        input.advance_to(2);
+        input.insert((4, 5));
+        input.insert((5, 4));
+        input.remove((0, 1));
+    }).unwrap();
+}
+
+#[test]
+fn query_plan_errors() {
+
+    let (empty, undefined, unbound, nullary) = timely::example(|scope| {
+
+        let rows = scope.new_collection_from(vec![vec![0u32, 1]]).1;
+
+        let empty =
+        Query::new()
+            .relation("edges", &rows)
+            .plan()
+            .err();
+        let undefined =
+        Query::new()
+            .relation("edges", &rows)
+            .atom("nodes", &[0])
+            .plan()
+            .err();
+        let unbound =
+        Query::new()
+            .relation("edges", &rows)
+            .atom("edges", &[0, 2])
+            .plan()
+            .err();
+        let nullary =
+        Query::new()
+            .relation("edges", &rows)
+            .atom("edges", &[0, 1])
+            .atom("edges", &[])
+            .plan()
+            .err();
+
+        (empty, undefined, unbound, nullary)
+    });
+
+    assert!(empty.expect("query should be rejected").contains("at least one atom"));
+    assert!(undefined.expect("query should be rejected").contains("undefined relation"));
+    assert!(unbound.expect("query should be rejected").contains("variable 1 is bound by no atom"));
+    assert!(nullary.expect("query should be rejected").contains("binds no variables"));
+}
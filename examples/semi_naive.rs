@@ -0,0 +1,94 @@
+extern crate timely;
+extern crate differential_dataflow;
+
+use std::io::{BufRead, BufReader};
+use std::fs::File;
+
+use timely::dataflow::operators::probe::Handle;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::operators::{Iterate, Threshold, Join, JoinCore, Consolidate};
+use differential_dataflow::operators::arrange::ArrangeByKey;
+use differential_dataflow::operators::iterate::SemiNaive;
+
+type Node = u32;
+
+/// Compares naive and semi-naive evaluation of the transitive closure of a graspan-style edge set.
+///
+/// The data file has lines `src dst label`, and only edges whose label matches the supplied label
+/// are used. The third argument is either `naive` or `semi`. The closure is linear in the relation,
+/// so the semi-naive rule joins the delta with the edges and has no use for the accumulated relation.
+///
+/// Running `examples/graspan.rs` on the same file gives a baseline for its hand-written semi-naive
+/// evaluation. The `doop` relations are defined by many mutually recursive `Variable`s, which a single
+/// `semi_naive` relation cannot express, and are not benchmarked here.
+fn main() {
+
+    let data_filename = std::env::args().nth(1).expect("Argument 1 (data filename) missing.");
+    let label = std::env::args().nth(2).expect("Argument 2 (edge label) missing.");
+    let semi_naive = std::env::args().nth(3).expect("Argument 3 (`naive` or `semi`) missing.") == "semi";
+
+    timely::execute_from_args(std::env::args(), move |worker| {
+
+        let timer = ::std::time::Instant::now();
+
+        let peers = worker.peers();
+        let index = worker.index();
+
+        let mut probe = Handle::new();
+        let mut input = worker.dataflow::<(),_,_>(|scope| {
+
+            let (input, edges) = scope.new_collection::<(Node, Node), isize>();
+
+            // reach(x, y) indexed by y, so that it can be extended along edges from y.
+            let transposed = edges.map(|(x, y)| (y, x));
+
+            let reach = if semi_naive {
+                transposed.semi_naive(|delta, _total| {
+                    let edges = edges.enter(&delta.scope()).arrange_by_key();
+                    delta.arrange_by_key()
+                         .join_core(&edges, |_y, &x, &z| Some((z, x)))
+                })
+            }
+            else {
+                transposed.iterate(|reach| {
+                    let edges = edges.enter(&reach.scope());
+                    reach.join_map(&edges, |_y, &x, &z| (z, x))
+                         .concat(reach)
+                         .distinct()
+                })
+            };
+
+            reach.map(|_| ())
+                 .consolidate()
+                 .inspect(move |x| println!("{:?}\tfinal size of closure: {:?}", timer.elapsed(), x.2))
+                 .probe_with(&mut probe);
+
+            input
+        });
+
+        let file = BufReader::new(File::open(&data_filename).expect("Failed to read data file"));
+        for readline in file.lines() {
+            let line = readline.ok().expect("read error");
+            if !line.starts_with('#') && line.len() > 0 {
+                let mut elts = line[..].split_whitespace();
+                let src: Node = elts.next().expect("data line with no src (1st) element").parse().expect("malformed src");
+                if (src as usize) % peers == index {
+                    let dst: Node = elts.next().expect("data line with no dst (2nd) element").parse().expect("malformed dst");
+                    let val: &str = elts.next().expect("data line with no val (3rd) element");
+                    if val == label {
+                        input.insert((src, dst));
+                    }
+                }
+            }
+        }
+
+        if index == 0 { println!("{:?}:\tData loaded", timer.elapsed()); }
+
+        input.close();
+        while worker.step() { }
+
+        if index == 0 { println!("{:?}:\tComputation complete ({})", timer.elapsed(), if semi_naive { "semi-naive" } else { "naive" }); }
+
+    }).expect("Timely computation did not complete cleanly");
+}
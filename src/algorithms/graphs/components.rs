@@ -0,0 +1,62 @@
+//! Weakly connected components, labeled by their smallest node.
+
+use std::hash::Hash;
+
+use timely::dataflow::*;
+
+use ::{Collection, ExchangeData};
+use ::operators::*;
+use ::lattice::Lattice;
+
+use super::symmetrize;
+use super::propagate::propagate;
+
+/// Returns pairs (node, label) indicating the smallest node in the weakly connected component of each
+/// node with an edge.
+///
+/// Labels are propagated along edges in both directions, and so the number of rounds is proportional
+/// to the diameter of the largest component.
+pub fn connected_components<G, N>(edges: &Collection<G, (N,N)>) -> Collection<G, (N,N)>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+{
+    let edges = symmetrize(edges);
+    let nodes = edges.map(|(src, _dst)| (src.clone(), src)).distinct();
+    propagate(&edges, &nodes)
+}
+
+/// Returns pairs (node, label) indicating the smallest node in the weakly connected component of each
+/// node with an edge, using pointer jumping.
+///
+/// In each round, each node takes the smallest of its label, the labels of its neighbors, and the label
+/// of the node its label names. Following labels roughly squares the distance a label travels in each
+/// round, and so the number of rounds is closer to the logarithm of the diameter, at the expense of a
+/// second join in each round.
+pub fn connected_components_jumping<G, N>(edges: &Collection<G, (N,N)>) -> Collection<G, (N,N)>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+{
+    use operators::arrange::arrangement::ArrangeByKey;
+
+    let edges = symmetrize(edges).arrange_by_key();
+    let nodes = edges.as_collection(|src, _dst| src.clone()).distinct().map(|node| (node.clone(), node));
+
+    nodes.iterate(|labels| {
+
+        let edges = edges.enter(&labels.scope());
+        let nodes = nodes.enter(&labels.scope());
+
+        // labels of neighbors, and labels of the nodes named by labels.
+        let neighbors = labels.join_core(&edges, |_src, label, dst| Some((dst.clone(), label.clone())));
+        let jumps = labels.map(|(node, label)| (label, node)).join_map(labels, |_label, node, jump| (node.clone(), jump.clone()));
+
+        labels.concat(&neighbors)
+              .concat(&jumps)
+              .concat(&nodes)
+              .reduce(|_, s, t| t.push((s[0].0.clone(), 1)))
+    })
+}
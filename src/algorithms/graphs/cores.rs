@@ -0,0 +1,74 @@
+//! K-core decomposition of undirected graphs.
+//!
+//! The k-core of a graph is its largest subgraph in which each node has at least `k` neighbors, and
+//! the core number of a node is the largest `k` for which the node is in the k-core. Edges are taken
+//! to be undirected, and self-loops are ignored.
+
+use std::hash::Hash;
+
+use timely::dataflow::*;
+
+use ::{Collection, ExchangeData};
+use ::operators::*;
+use ::lattice::Lattice;
+
+use super::symmetrize;
+
+/// Returns the edges of the k-core of `edges`, in both directions.
+///
+/// Nodes with fewer than `k` neighbors are repeatedly removed, along with their edges, until all nodes
+/// that remain have at least `k` neighbors.
+pub fn k_core<G, N>(edges: &Collection<G, (N,N)>, k: usize) -> Collection<G, (N,N)>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+{
+    symmetrize(&edges.filter(|&(ref src, ref dst)| src != dst)).iterate(|inner| {
+
+        // nodes with at least `k` remaining neighbors.
+        let active =
+        inner.map(|(src, _dst)| src)
+             .count()
+             .filter(move |&(_, degree)| degree as usize >= k)
+             .map(|(node, _)| node);
+
+        inner.semijoin(&active)
+             .map(|(src, dst)| (dst, src))
+             .semijoin(&active)
+             .map(|(dst, src)| (src, dst))
+    })
+}
+
+/// Returns pairs (node, core) indicating the core number of each node with a neighbor.
+///
+/// Each node's estimate starts at its degree, and is repeatedly replaced by the h-index of its
+/// neighbors' estimates: the largest `h` such that at least `h` neighbors have estimates of at least
+/// `h`. The estimates only decrease, and converge to the core numbers.
+pub fn core_numbers<G, N>(edges: &Collection<G, (N,N)>) -> Collection<G, (N,usize)>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+{
+    let edges = symmetrize(&edges.filter(|&(ref src, ref dst)| src != dst));
+    let degrees = edges.map(|(src, _dst)| src).count().map(|(node, degree)| (node, degree as usize));
+
+    degrees.iterate(|estimates| {
+
+        let edges = edges.enter(&estimates.scope());
+
+        estimates
+            .join_map(&edges, |_src, estimate, dst| (dst.clone(), *estimate))
+            .reduce(|_node, estimates, output| {
+                // estimates are sorted, and so visited from the largest down.
+                let mut at_least = 0;
+                let mut h_index = 0;
+                for &(estimate, count) in estimates.iter().rev() {
+                    at_least += count as usize;
+                    h_index = ::std::cmp::max(h_index, ::std::cmp::min(*estimate, at_least));
+                }
+                output.push((h_index, 1));
+            })
+    })
+}
@@ -0,0 +1,41 @@
+//! Node degrees and their distribution.
+
+use std::hash::Hash;
+
+use timely::dataflow::*;
+
+use ::{Collection, ExchangeData};
+use ::operators::*;
+use ::lattice::Lattice;
+
+/// Returns pairs (node, degree) indicating the number of edges leaving each node with an edge.
+pub fn out_degrees<G, N>(edges: &Collection<G, (N,N)>) -> Collection<G, (N,isize)>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+{
+    edges.map(|(src, _dst)| src).count()
+}
+
+/// Returns pairs (node, degree) indicating the number of edges entering each node with an edge.
+pub fn in_degrees<G, N>(edges: &Collection<G, (N,N)>) -> Collection<G, (N,isize)>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+{
+    edges.map(|(_src, dst)| dst).count()
+}
+
+/// Returns pairs (degree, count) indicating the number of nodes with each degree.
+///
+/// Nodes with degree zero are not represented, as `degrees` has no records for them.
+pub fn distribution<G, N>(degrees: &Collection<G, (N,isize)>) -> Collection<G, (isize,isize)>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+{
+    degrees.map(|(_node, degree)| degree).count()
+}
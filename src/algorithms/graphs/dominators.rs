@@ -0,0 +1,84 @@
+//! Dominators in graphs with entry nodes, such as control-flow graphs.
+//!
+//! A node `d` dominates a node `n` if every path from an entry node to `n` passes through `d`. Each
+//! node reachable from the entries dominates itself, and its other dominators form a chain whose
+//! nearest element is its immediate dominator, its parent in the dominator tree.
+//!
+//! The computation uses the fact that `d` does not dominate `n` exactly when `n` can be reached from an
+//! entry without passing through `d`, and maintains this reachability for every reachable `d` at once.
+//! Its cost is proportional to the number of reachable nodes times the number of edges, which suits
+//! graphs like the control-flow graphs of individual procedures.
+
+use std::hash::Hash;
+
+use timely::dataflow::*;
+
+use ::{Collection, ExchangeData};
+use ::operators::*;
+use ::lattice::Lattice;
+
+use super::reachability::reachable;
+
+/// Returns pairs (node, dominator) indicating that `dominator` dominates `node`, for each node reachable
+/// from `entries`.
+///
+/// All pairs of reachable nodes are formed as candidates, by joining the reachable nodes with themselves
+/// on a unit key. This takes time and space quadratic in the number of reachable nodes, and as all
+/// records share one key, the join is performed by a single worker regardless of how many are present.
+pub fn dominators<G, N>(edges: &Collection<G, (N,N)>, entries: &Collection<G, N>) -> Collection<G, (N,N)>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+{
+    use operators::arrange::arrangement::ArrangeByKey;
+
+    let entries = entries.distinct();
+    let nodes = reachable(edges, &entries);
+    let edges = edges.arrange_by_key();
+
+    // all pairs of reachable nodes, as candidate (node, dominator) pairs.
+    let candidates = nodes.map(|node| ((), node));
+    let pairs = candidates.join_map(&candidates, |&(), node, dominator| (node.clone(), dominator.clone()));
+
+    // pairs (node, avoided) for entries and each other reachable node.
+    let starts = pairs.semijoin(&entries).filter(|&(ref entry, ref avoided)| entry != avoided);
+
+    // pairs (node, avoided) where `node` is reachable from an entry without passing through `avoided`.
+    let avoids = starts.iterate(|avoids| {
+
+        let edges = edges.enter(&avoids.scope());
+        let starts = starts.enter(&avoids.scope());
+
+        avoids.join_core(&edges, |_node, avoided, next| Some((next.clone(), avoided.clone())))
+              .filter(|&(ref next, ref avoided)| next != avoided)
+              .concat(&starts)
+              .distinct()
+    });
+
+    pairs.concat(&avoids.negate())
+}
+
+/// Returns pairs (node, parent) indicating the immediate dominator of each node reachable from
+/// `entries`, other than the entries themselves.
+///
+/// The immediate dominator of a node is its strict dominator with the most dominators.
+pub fn dominator_tree<G, N>(edges: &Collection<G, (N,N)>, entries: &Collection<G, N>) -> Collection<G, (N,N)>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+{
+    let dominators = dominators(edges, entries);
+    let depths = dominators.map(|(node, _dominator)| node).count();
+
+    dominators
+        .filter(|&(ref node, ref dominator)| node != dominator)
+        .map(|(node, dominator)| (dominator, node))
+        .join_map(&depths, |dominator, node, &depth| (node.clone(), (depth, dominator.clone())))
+        .reduce(|_node, candidates, output| {
+            // candidates are sorted by depth, and the deepest is last.
+            let &(_, ref parent) = candidates[candidates.len() - 1].0;
+            output.push((parent.clone(), 1));
+        })
+}
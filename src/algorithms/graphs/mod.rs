@@ -1,7 +1,37 @@
 //! Methods for graph processing.
 
+use std::hash::Hash;
+
+use timely::dataflow::Scope;
+
+use ::{Collection, ExchangeData};
+use ::operators::*;
+use ::lattice::Lattice;
+
 pub mod scc;
 pub mod sequential;
 pub mod bijkstra;
 pub mod bfs;
-pub mod propagate;
\ No newline at end of file
+pub mod propagate;
+pub mod motifs;
+pub mod sssp;
+pub mod pagerank;
+pub mod components;
+pub mod degrees;
+pub mod cores;
+pub mod reachability;
+pub mod dominators;
+
+/// Adds the reverse of each edge, without duplicating edges.
+///
+/// Self-loops are retained, and callers that ignore them should filter them out first.
+pub(crate) fn symmetrize<G, N>(edges: &Collection<G, (N,N)>) -> Collection<G, (N,N)>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+{
+    edges.map(|(src, dst)| (dst, src))
+         .concat(edges)
+         .distinct()
+}
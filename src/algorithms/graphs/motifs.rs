@@ -0,0 +1,108 @@
+//! Incrementally maintained subgraph matching and counting.
+//!
+//! A pattern is a small directed graph on nodes `0 .. k`, described by its edges, and a match of the
+//! pattern is an assignment of distinct graph nodes to the pattern nodes such that each pattern edge
+//! is a graph edge. Matches are maintained with `DeltaJoin`, which uses an arrangement of the edges for
+//! each way they are looked up and does not arrange partial matches.
+//!
+//! Symmetric patterns have several matches for each subgraph, one for each automorphism. The triangle
+//! and clique methods orient undirected edges from smaller to larger node, so that each subgraph has
+//! exactly one match.
+
+use std::hash::Hash;
+
+use timely::dataflow::*;
+
+use ::{Collection, ExchangeData};
+use ::operators::*;
+use ::operators::delta::DeltaJoin;
+use ::lattice::Lattice;
+
+/// Returns the matches of `pattern` in the directed graph `edges`, indexed by pattern node.
+///
+/// An error is returned if the pattern has no edges, if some pattern node below the largest is in no
+/// pattern edge, or if the pattern is not connected.
+pub fn pattern<G, N>(edges: &Collection<G, (N,N)>, pattern: &[(usize, usize)]) -> Result<Collection<G, Vec<N>>, String>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+{
+    let rows = edges.map(|(src, dst)| vec![src, dst]);
+    let mut join = DeltaJoin::new();
+    for &(src, dst) in pattern.iter() {
+        join.relation(&rows, &[src, dst]);
+    }
+
+    // matches assign distinct nodes to distinct pattern nodes.
+    join.join()
+        .map(|matches| matches.filter(|nodes| (1 .. nodes.len()).all(|i| !nodes[.. i].contains(&nodes[i]))))
+}
+
+/// Returns the triangles `[a, b, c]` of the undirected graph `edges`, with `a < b < c`.
+pub fn triangles<G, N>(edges: &Collection<G, (N,N)>) -> Collection<G, Vec<N>>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+{
+    pattern(&orient(edges), &[(0,1), (0,2), (1,2)]).expect("well-formed pattern")
+}
+
+/// Returns the four-cliques `[a, b, c, d]` of the undirected graph `edges`, with `a < b < c < d`.
+pub fn four_cliques<G, N>(edges: &Collection<G, (N,N)>) -> Collection<G, Vec<N>>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+{
+    pattern(&orient(edges), &[(0,1), (0,2), (0,3), (1,2), (1,3), (2,3)]).expect("well-formed pattern")
+}
+
+/// Returns the simple directed paths of `length` edges in `edges`, as lists of `length + 1` nodes.
+///
+/// # Panics
+///
+/// Panics if `length` is zero.
+pub fn paths<G, N>(edges: &Collection<G, (N,N)>, length: usize) -> Collection<G, Vec<N>>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+{
+    assert!(length > 0, "paths must have at least one edge");
+    let path = (0 .. length).map(|i| (i, i+1)).collect::<Vec<_>>();
+    pattern(edges, &path[..]).expect("well-formed pattern")
+}
+
+/// Returns pairs (node, count) indicating the number of matches in which each node participates.
+pub fn counts_per_node<G, N>(matches: &Collection<G, Vec<N>>) -> Collection<G, (N, isize)>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+{
+    matches.flat_map(|nodes| nodes).count()
+}
+
+/// Returns the number of matches, as a collection containing `((), count)` when there are matches.
+pub fn count_total<G, N>(matches: &Collection<G, Vec<N>>) -> Collection<G, ((), isize)>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+{
+    matches.map(|_| ()).count()
+}
+
+/// Orients undirected edges from smaller to larger node, removing self-loops and duplicates.
+fn orient<G, N>(edges: &Collection<G, (N,N)>) -> Collection<G, (N,N)>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+{
+    edges.filter(|&(ref src, ref dst)| src != dst)
+         .map(|(src, dst)| if src < dst { (src, dst) } else { (dst, src) })
+         .distinct()
+}
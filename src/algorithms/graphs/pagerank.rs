@@ -0,0 +1,98 @@
+//! PageRank and personalized PageRank, as counts of random surfers.
+//!
+//! Ranks are integers, carried as the multiplicities of nodes: each node starts with `SURFERS` surfers,
+//! and in each step the surfers at a node are divided evenly among its out-edges after a fraction of
+//! them, determined by the damping factor, stop. Each node then receives the surfers that stopped in
+//! expectation, so that the total number of surfers stays roughly constant. Integer division discards
+//! remainders, and surfers at nodes without out-edges are lost.
+//!
+//! As all arithmetic is on integers, the incrementally maintained ranks are exactly those a computation
+//! from scratch on the same edges would produce.
+
+use std::hash::Hash;
+
+use timely::dataflow::*;
+
+use ::{Collection, ExchangeData};
+use ::operators::*;
+use ::lattice::Lattice;
+
+/// The number of surfers each node starts with.
+pub const SURFERS: isize = 1_000_000;
+
+/// The resolution with which damping factors are represented.
+const DAMPING_SCALE: isize = 1_000_000;
+
+/// Returns the rank of each node with an edge, as its multiplicity, after `iterations` steps.
+///
+/// The damping factor is the fraction of surfers that follow an out-edge in each step, and is typically
+/// `0.85`. If `iterations` is zero, each node has `SURFERS` surfers.
+pub fn pagerank<G, N>(edges: &Collection<G, (N,N)>, iterations: u64, damping: f64) -> Collection<G, N>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+{
+    let nodes = edges.flat_map(|(src, dst)| Some(src).into_iter().chain(Some(dst))).distinct();
+    surf(edges, &nodes, iterations, damping)
+}
+
+/// Returns the rank of each node personalized to `sources`, as its multiplicity, after `iterations` steps.
+///
+/// Surfers start only at the sources, and surfers that stop resume only at the sources, so that ranks
+/// reflect proximity to the sources. Repeated sources count once.
+pub fn personalized_pagerank<G, N>(edges: &Collection<G, (N,N)>, sources: &Collection<G, N>, iterations: u64, damping: f64) -> Collection<G, N>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+{
+    let sources = sources.distinct();
+    surf(edges, &sources, iterations, damping)
+}
+
+/// Moves surfers starting at `starts` along `edges`, resuming stopped surfers where they started.
+fn surf<G, N>(edges: &Collection<G, (N,N)>, starts: &Collection<G, N>, iterations: u64, damping: f64) -> Collection<G, N>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+{
+    assert!(0.0 <= damping && damping <= 1.0, "damping factor must be between zero and one");
+    let follow = (damping * DAMPING_SCALE as f64) as isize;
+    let resume = SURFERS - followed(SURFERS, follow);
+
+    // each edge annotated with the out-degree of its source.
+    let degrees = edges.map(|(src, _dst)| src).count();
+    let edges = edges.join_map(&degrees, |src, dst, &degree| (src.clone(), (dst.clone(), degree)));
+
+    let resumes = starts.explode(move |node| Some((node, resume)));
+    let starts = starts.explode(|node| Some((node, SURFERS)));
+
+    // `iterate_bounded` takes at least one step.
+    if iterations == 0 {
+        return starts;
+    }
+
+    let (ranks, _residual) =
+    starts.iterate_bounded(iterations, |ranks| {
+
+        let edges = edges.enter(&ranks.scope());
+        let resumes = resumes.enter(&ranks.scope());
+
+        // surfers following each edge, from the surfers at its source.
+        edges.semijoin(ranks)
+             .threshold(move |&(_, (_, degree)), &surfers| followed(surfers, follow) / degree)
+             .map(|(_src, (dst, _degree))| dst)
+             .concat(&resumes)
+    });
+
+    ranks
+}
+
+/// The number of `surfers` that follow an out-edge, for the scaled damping factor `follow`.
+///
+/// The product is formed in 128 bits, as a node may hold many times `SURFERS` surfers.
+fn followed(surfers: isize, follow: isize) -> isize {
+    (surfers as i128 * follow as i128 / DAMPING_SCALE as i128) as isize
+}
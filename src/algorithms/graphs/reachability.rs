@@ -0,0 +1,63 @@
+//! Reachability from sets of source nodes.
+
+use std::hash::Hash;
+
+use timely::dataflow::*;
+
+use ::{Collection, ExchangeData};
+use ::operators::*;
+use ::lattice::Lattice;
+
+/// Returns pairs (source, node) indicating that `node` is reachable from `source` along directed edges.
+///
+/// This is the transitive closure restricted to paths that start at `sources`, and each source reaches
+/// itself. The result can be as large as the number of sources times the number of nodes, and so this
+/// is best suited to small source sets, or to graphs like control-flow graphs with few nodes.
+pub fn transitive_closure<G, N>(edges: &Collection<G, (N,N)>, sources: &Collection<G, N>) -> Collection<G, (N,N)>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+{
+    use operators::arrange::arrangement::ArrangeByKey;
+
+    let edges = edges.arrange_by_key();
+
+    // pairs (node, source), keyed by node to extend along edges.
+    let sources = sources.distinct().map(|source| (source.clone(), source));
+
+    sources.iterate(|reach| {
+
+        let edges = edges.enter(&reach.scope());
+        let sources = sources.enter(&reach.scope());
+
+        reach.join_core(&edges, |_node, source, next| Some((next.clone(), source.clone())))
+             .concat(&sources)
+             .distinct()
+    })
+    .map(|(node, source)| (source, node))
+}
+
+/// Returns the nodes reachable from any of `sources` along directed edges, including the sources.
+pub fn reachable<G, N>(edges: &Collection<G, (N,N)>, sources: &Collection<G, N>) -> Collection<G, N>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+{
+    use operators::arrange::arrangement::ArrangeByKey;
+
+    let edges = edges.arrange_by_key();
+    let sources = sources.distinct();
+
+    sources.iterate(|reach| {
+
+        let edges = edges.enter(&reach.scope());
+        let sources = sources.enter(&reach.scope());
+
+        reach.map(|node| (node, ()))
+             .join_core(&edges, |_node, &(), next| Some(next.clone()))
+             .concat(&sources)
+             .distinct()
+    })
+}
@@ -9,13 +9,24 @@ use ::lattice::Lattice;
 use ::operators::*;
 use hashable::Hashable;
 
-fn _color<G, N>(edges: &Collection<G, (N,N)>) -> Collection<G,(N,Option<u32>)>
+use super::symmetrize;
+
+/// Returns pairs (node, color) of a greedy coloring of the undirected graph `edges`.
+///
+/// Nodes are colored in order of their identifiers, each with the least positive color not used by
+/// its neighbors with smaller identifiers. The coloring is a deterministic function of the edges, and
+/// so the incrementally maintained coloring is always the coloring a computation from scratch would
+/// produce; a change to an edge may recolor any nodes with larger identifiers. Only nodes with edges
+/// to other nodes are colored.
+pub fn color<G, N>(edges: &Collection<G, (N,N)>) -> Collection<G,(N,u32)>
 where
     G: Scope,
-    G::Timestamp: Lattice+Ord,
+    G::Timestamp: Lattice+Hash+Ord,
     N: ExchangeData+Hash,
 {
-    // need some bogus initial values.
+    let edges = symmetrize(&edges.filter(|&(ref src, ref dst)| src != dst));
+
+    // colors of nodes not yet colored, which no node can use.
     let start = edges.map(|(x,_y)| (x,u32::max_value()))
                      .distinct();
 
@@ -24,12 +35,39 @@ where
 
         // look for the first absent positive integer.
         // start at 1 in case we ever use NonZero<u32>.
-
+        //
+        // `vals` holds the distinct colors of neighbors in sorted order, so the first position `i - 1`
+        // not holding `i` identifies `i` as absent. Neighbors not yet colored hold `u32::max_value()`,
+        // which sorts after every color and so never hides an absent one.
         (1u32 ..)
             .filter(|&i| vals.get(i as usize - 1).map(|x| *x.0) != Some(i))
             .next()
             .unwrap()
     })
+    .flat_map(|(node, color)| color.map(|color| (node, color)))
+}
+
+/// Returns the nodes of a greedy maximal independent set of the undirected graph `edges`.
+///
+/// Nodes are considered in order of their identifiers, and each is added to the set if none of its
+/// neighbors with smaller identifiers were. As with `color`, the set is a deterministic function of the
+/// edges, and a change to an edge may change membership of any nodes with larger identifiers. Nodes
+/// without edges to other nodes are not reported, though they belong to every maximal independent set.
+pub fn independent_set<G, N>(edges: &Collection<G, (N,N)>) -> Collection<G, N>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Hash+Ord,
+    N: ExchangeData+Hash,
+{
+    let edges = symmetrize(&edges.filter(|&(ref src, ref dst)| src != dst));
+
+    // nodes not yet considered do not exclude their neighbors.
+    let start = edges.map(|(x,_y)| (x,false))
+                     .distinct();
+
+    sequence(&start, &edges, |_node, vals| !vals.iter().any(|x| *x.0))
+        .filter(|&(_, ref member)| member == &Some(true))
+        .map(|(node, _)| node)
 }
 
 /// Applies `logic` to nodes sequentially, in order of node identifiers.
@@ -53,15 +91,11 @@ where
     F: Fn(&N, &[(&V, isize)])->V+'static
 {
 
-    let timer = ::std::time::Instant::now();
-
     // start iteration with None messages for all.
     state
         .map(|(node, _state)| (node, None))
         .iterate(|new_state| {
 
-            new_state.map(|x| x.1.is_some()).consolidate().inspect(move |x| println!("{:?}\t{:?}", timer.elapsed(), x));
-
             // immutable content: edges and initial state.
             let edges = edges.enter(&new_state.scope());
             let old_state = state.enter(&new_state.scope());
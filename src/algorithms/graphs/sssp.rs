@@ -0,0 +1,56 @@
+//! Weighted shortest path distance labeling.
+
+use std::hash::Hash;
+use std::ops::Add;
+
+use timely::dataflow::*;
+
+use ::{Collection, ExchangeData};
+use ::operators::*;
+use ::operators::arrange::{Arranged, ArrangeByKey};
+use ::lattice::Lattice;
+use ::trace::{TraceReader, BatchReader, Cursor};
+
+/// Returns pairs (node, dist) indicating the least total weight of a path to each node from a root.
+///
+/// Edges are triples `(src, dst, weight)`, and weights must not be negative: the distance from a root
+/// to itself is `W::default()`, taken to be zero, and distances must not decrease along edges. Changing
+/// the weight of an edge is its removal with the old weight and insertion with the new weight.
+pub fn sssp<G, N, W>(edges: &Collection<G, (N,N,W)>, roots: &Collection<G, N>) -> Collection<G, (N,W)>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+    W: ExchangeData+Hash+Default+Add<Output=W>,
+{
+    let edges = edges.map(|(src, dst, weight)| (src, (dst, weight))).arrange_by_key();
+    sssp_arranged(&edges, roots)
+}
+
+/// Returns pairs (node, dist) indicating the least total weight of a path to each node from a root.
+///
+/// Edges are arranged by source, with values `(dst, weight)`.
+pub fn sssp_arranged<G, N, W, Tr>(edges: &Arranged<G, Tr>, roots: &Collection<G, N>) -> Collection<G, (N, W)>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    N: ExchangeData+Hash,
+    W: ExchangeData+Hash+Default+Add<Output=W>,
+    Tr: TraceReader<Key=N, Val=(N,W), Time=G::Timestamp, R=isize>+Clone+'static,
+    Tr::Batch: BatchReader<N, (N,W), G::Timestamp, Tr::R>+'static,
+    Tr::Cursor: Cursor<N, (N,W), G::Timestamp, Tr::R>+'static,
+{
+    // initialize roots as reaching themselves at distance zero
+    let nodes = roots.map(|x| (x, W::default()));
+
+    // repeatedly update minimal distances each node can be reached from each root
+    nodes.iterate(|inner| {
+
+        let edges = edges.enter(&inner.scope());
+        let nodes = nodes.enter(&inner.scope());
+
+        inner.join_core(&edges, |_src, dist, &(ref dst, ref weight)| Some((dst.clone(), dist.clone() + weight.clone())))
+             .concat(&nodes)
+             .reduce(|_, s, t| t.push((s[0].0.clone(), 1)))
+    })
+}
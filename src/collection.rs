@@ -19,7 +19,7 @@ use timely::dataflow::{Scope, Stream};
 use timely::dataflow::operators::*;
 
 use ::difference::{Semigroup, Abelian};
-use lattice::Lattice;
+use lattice::{Lattice, Bitemporal};
 use hashable::Hashable;
 
 /// A mutable collection of values of type `D`
@@ -607,6 +607,53 @@ impl<G: Scope, D: Data, R: Abelian> Collection<G, D, R> where G::Timestamp: Data
     }
 }
 
+impl<G: Scope<Timestamp=Bitemporal>, D: Data, R: Semigroup> Collection<G, D, R> {
+    /// The collection as known at system time `system` about event times up through `event`.
+    ///
+    /// The result contains the updates of `self` at times less or equal to `(system, event)`, advanced
+    /// to that time. Its accumulation at times greater or equal to `(system, event)` is the accumulation
+    /// of `self` at `(system, event)`, and it does not change as later corrections are recorded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate timely;
+    /// extern crate differential_dataflow;
+    ///
+    /// use timely::Configuration;
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::lattice::Bitemporal;
+    ///
+    /// fn main() {
+    ///     ::timely::execute(Configuration::Thread, |worker| {
+    ///
+    ///         let mut input = worker.dataflow::<Bitemporal,_,_>(|scope| {
+    ///             let (input, balances) = scope.new_collection::<(&'static str, u64), isize>();
+    ///             // as known at system time 1, about event time 5.
+    ///             balances.as_of(1, 5)
+    ///                     .inspect(|x| println!("{:?}", x));
+    ///             input
+    ///         });
+    ///
+    ///         input.update_at_event(("alice", 10), 5, 1);
+    ///         input.advance_system_to(2);
+    ///         // a correction, recorded at system time 2 about event time 5.
+    ///         input.update_at_event(("alice", 10), 5, -1);
+    ///         input.update_at_event(("alice", 12), 5, 1);
+    ///
+    ///     }).unwrap();
+    /// }
+    /// ```
+    pub fn as_of(&self, system: u64, event: u64) -> Collection<G, D, R> {
+        use timely::order::PartialOrder;
+        let time = Bitemporal::new(system, event);
+        self.inner
+            .filter(move |x| x.1.less_equal(&time))
+            .map(move |(data, _, diff)| (data, time, diff))
+            .as_collection()
+    }
+}
+
 /// Conversion to a differential dataflow Collection.
 pub trait AsCollection<G: Scope, D: Data, R: Semigroup> {
     /// Converts the type to a differential dataflow collection.
@@ -5,6 +5,13 @@
 //! is when we maintain both a count and another accumulation, for example height. The differential
 //! dataflow collections would then track for each record the total of counts and heights, which allows
 //! us to track something like the average.
+//!
+//! Beyond the integers, `Rational` provides exact fractional accumulation, `MinPlus` is the tropical
+//! semiring whose multiplication by `join` combines path lengths, and `Checked` is an integer that
+//! records overflow rather than wrapping.
+//!
+//! Structs of named differences can derive `Semigroup`, `Monoid`, `Abelian` (through `Neg`), and `Mul`
+//! field by field, using the `differential-derive` crate in the `derive` directory.
 
 use std::ops::{AddAssign, Neg, Mul};
 use std::iter::Iterator;
@@ -131,6 +138,12 @@ impl<R1: Semigroup, R2: Semigroup> Semigroup for DiffPair<R1, R2> {
 	}
 }
 
+impl<R1: Monoid, R2: Monoid> Monoid for DiffPair<R1, R2> {
+	#[inline] fn zero() -> Self {
+		DiffPair::new(R1::zero(), R2::zero())
+	}
+}
+
 impl<'a, R1: AddAssign<&'a R1>, R2: AddAssign<&'a R2>> AddAssign<&'a DiffPair<R1, R2>> for DiffPair<R1, R2> {
 	#[inline] fn add_assign(&mut self, rhs: &'a Self) {
 		self.element1 += &rhs.element1;
@@ -251,3 +264,306 @@ impl<T: Copy, R: Mul<T>> Mul<T> for DiffVector<R> {
 		DiffVector { buffer }
 	}
 }
+
+/// An exact rational number, for example a monetary amount.
+///
+/// Floating point numbers do not implement `Ord`, and their addition is neither associative nor
+/// exact; accumulations of the same updates in different orders may differ and may never return to
+/// zero. A `Rational` is kept in lowest terms with a positive denominator, and its arithmetic panics
+/// on overflow rather than losing precision. Fixed-point amounts, like cents, use a power of ten as
+/// the denominator.
+///
+/// # Examples
+///
+/// ```
+/// use differential_dataflow::difference::{Semigroup, Rational};
+///
+/// let mut total = Rational::new(1999, 100);   // 19.99
+/// total += &Rational::new(1, 3);
+/// total += &Rational::new(-1999, 100);
+/// assert_eq!(total, Rational::new(1, 3));
+/// total += &Rational::new(-2, 6);
+/// assert!(total.is_zero());
+///
+/// // comparison does not overflow, even where cross-multiplication would.
+/// let max = i128::max_value();
+/// assert!(Rational::new(max - 1, max) < Rational::new(max, max - 1));
+/// ```
+#[derive(Abomonation, Copy, Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct Rational {
+	numer: i128,
+	denom: i128,
+}
+
+impl Rational {
+	/// Creates a new rational number `numer / denom`.
+	///
+	/// Any `i128` values are accepted, including `i128::min_value()`, as long as the result in lowest
+	/// terms has a numerator and a positive denominator that are `i128` values.
+	///
+	/// # Panics
+	///
+	/// Panics if `denom` is zero, or if the result in lowest terms cannot be represented, as for
+	/// `i128::min_value() / -1` or `1 / i128::min_value()`.
+	pub fn new(numer: i128, denom: i128) -> Self {
+		assert!(denom != 0, "Rational with zero denominator");
+		// Reduce the magnitudes, which unlike the values themselves can always be negated.
+		let gcd = gcd(numer.unsigned_abs(), denom.unsigned_abs());
+		let negative = (numer < 0) != (denom < 0);
+		Rational {
+			numer: signed(numer.unsigned_abs() / gcd, negative),
+			denom: signed(denom.unsigned_abs() / gcd, false),
+		}
+	}
+	/// Creates a new rational number from an integer.
+	#[inline] pub fn from_integer(value: i128) -> Self {
+		Rational { numer: value, denom: 1 }
+	}
+	/// The numerator, in lowest terms.
+	#[inline] pub fn numer(&self) -> i128 { self.numer }
+	/// The denominator, in lowest terms and positive.
+	#[inline] pub fn denom(&self) -> i128 { self.denom }
+}
+
+/// The greatest common divisor of `a` and `b`, or one if both are zero.
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+	while b != 0 {
+		let t = a % b;
+		a = b;
+		b = t;
+	}
+	if a == 0 { 1 } else { a }
+}
+
+/// The magnitude `value`, negated if `negative`.
+///
+/// # Panics
+///
+/// Panics if the result is not an `i128`.
+fn signed(value: u128, negative: bool) -> i128 {
+	if negative {
+		assert!(value <= i128::min_value().unsigned_abs(), "Rational overflow");
+		// The magnitude of `i128::min_value()` wraps to itself.
+		(value as i128).wrapping_neg()
+	}
+	else {
+		assert!(value <= i128::max_value() as u128, "Rational overflow");
+		value as i128
+	}
+}
+
+impl Ord for Rational {
+	fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+		use std::cmp::Ordering;
+		// Cross-multiplication can overflow, so compare continued fraction expansions instead: whole
+		// parts first, and then the reciprocals of the fractional parts, in reverse order.
+		let (mut a, mut b) = (self.numer, self.denom);
+		let (mut c, mut d) = (other.numer, other.denom);
+		let mut reversed = false;
+		loop {
+			let (whole1, frac1) = (a.div_euclid(b), a.rem_euclid(b));
+			let (whole2, frac2) = (c.div_euclid(d), c.rem_euclid(d));
+			let order = match (whole1.cmp(&whole2), frac1 == 0, frac2 == 0) {
+				(Ordering::Equal, true, true) => Ordering::Equal,
+				(Ordering::Equal, true, false) => Ordering::Less,
+				(Ordering::Equal, false, true) => Ordering::Greater,
+				(Ordering::Equal, false, false) => {
+					// frac1 / b against frac2 / d is b / frac1 against d / frac2, reversed.
+					a = b; b = frac1;
+					c = d; d = frac2;
+					reversed = !reversed;
+					continue;
+				},
+				(order, _, _) => order,
+			};
+			return if reversed { order.reverse() } else { order };
+		}
+	}
+}
+
+impl PartialOrd for Rational {
+	fn partial_cmp(&self, other: &Self) -> Option<::std::cmp::Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Semigroup for Rational {
+	#[inline] fn is_zero(&self) -> bool { self.numer == 0 }
+}
+
+impl Monoid for Rational {
+	#[inline] fn zero() -> Self { Rational::from_integer(0) }
+}
+
+impl<'a> AddAssign<&'a Rational> for Rational {
+	fn add_assign(&mut self, rhs: &'a Self) {
+		// Denominators are positive, and so is their greatest common divisor.
+		let gcd = gcd(self.denom as u128, rhs.denom as u128) as i128;
+		let numer =
+		self.numer
+			.checked_mul(rhs.denom / gcd)
+			.and_then(|x| rhs.numer.checked_mul(self.denom / gcd).and_then(|y| x.checked_add(y)))
+			.expect("Rational overflow");
+		let denom = (self.denom / gcd).checked_mul(rhs.denom).expect("Rational overflow");
+		*self = Rational::new(numer, denom);
+	}
+}
+
+impl Neg for Rational {
+	type Output = Rational;
+	#[inline] fn neg(self) -> Self::Output {
+		Rational { numer: self.numer.checked_neg().expect("Rational overflow"), denom: self.denom }
+	}
+}
+
+impl Mul<Rational> for Rational {
+	type Output = Rational;
+	fn mul(self, other: Rational) -> Self::Output {
+		// Cancel common factors first, to postpone overflow.
+		// Each divides a positive denominator, and so is at most `i128::max_value()`.
+		let gcd1 = gcd(self.numer.unsigned_abs(), other.denom as u128) as i128;
+		let gcd2 = gcd(other.numer.unsigned_abs(), self.denom as u128) as i128;
+		let numer = (self.numer / gcd1).checked_mul(other.numer / gcd2).expect("Rational overflow");
+		let denom = (self.denom / gcd2).checked_mul(other.denom / gcd1).expect("Rational overflow");
+		Rational::new(numer, denom)
+	}
+}
+
+impl Mul<isize> for Rational {
+	type Output = Rational;
+	fn mul(self, other: isize) -> Self::Output {
+		self * Rational::from_integer(other as i128)
+	}
+}
+
+/// An element of the min-plus (tropical) semiring over `u64`, for example a path length.
+///
+/// Addition takes the minimum and multiplication adds the values, so that `join` of two collections
+/// of `MinPlus` differences produces the lengths of combined paths, and accumulation retains the
+/// shortest. The zero element is infinity, represented by `u64::max_value()`, and multiplication
+/// saturates there.
+///
+/// There is no negation, and a shorter length can never be retracted once added. `MinPlus` is only
+/// appropriate for collections whose lengths only decrease, and for operators that require only a
+/// `Semigroup` or `Monoid`.
+///
+/// # Examples
+///
+/// ```
+/// use differential_dataflow::difference::{Semigroup, Monoid, MinPlus};
+///
+/// let mut shortest = MinPlus::new(10);
+/// shortest += &(MinPlus::new(3) * MinPlus::new(4));
+/// assert_eq!(shortest, MinPlus::new(7));
+/// assert!(MinPlus::zero().is_zero());
+/// assert!((MinPlus::zero() * shortest).is_zero());
+/// ```
+#[derive(Abomonation, Copy, Ord, PartialOrd, Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct MinPlus {
+	/// The value, where `u64::max_value()` represents infinity.
+	pub value: u64,
+}
+
+impl MinPlus {
+	/// Creates a new element with value `value`.
+	#[inline] pub fn new(value: u64) -> Self { MinPlus { value } }
+}
+
+impl Semigroup for MinPlus {
+	#[inline] fn is_zero(&self) -> bool { self.value == u64::max_value() }
+}
+
+impl Monoid for MinPlus {
+	#[inline] fn zero() -> Self { MinPlus::new(u64::max_value()) }
+}
+
+impl<'a> AddAssign<&'a MinPlus> for MinPlus {
+	#[inline] fn add_assign(&mut self, rhs: &'a Self) {
+		if rhs.value < self.value {
+			self.value = rhs.value;
+		}
+	}
+}
+
+impl Mul<MinPlus> for MinPlus {
+	type Output = MinPlus;
+	#[inline] fn mul(self, other: MinPlus) -> Self::Output {
+		MinPlus::new(self.value.saturating_add(other.value))
+	}
+}
+
+/// A signed integer difference that records overflow rather than wrapping.
+///
+/// Once an accumulation overflows it remains overflowed, through further additions, negations, and
+/// multiplications. An overflowed difference is never zero, and so is never retired; it remains
+/// visible in outputs, where `overflowed` reports it.
+///
+/// # Examples
+///
+/// ```
+/// use differential_dataflow::difference::{Semigroup, Checked};
+///
+/// let mut count = Checked::new(i8::max_value());
+/// count += &Checked::new(1);
+/// count += &Checked::new(-1);
+/// assert!(count.overflowed());
+/// assert!(!count.is_zero());
+/// ```
+#[derive(Abomonation, Copy, Ord, PartialOrd, Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct Checked<T> {
+	value: Option<T>,
+}
+
+impl<T: Copy> Checked<T> {
+	/// Creates a new difference with value `value`.
+	#[inline] pub fn new(value: T) -> Self { Checked { value: Some(value) } }
+	/// The value, or `None` if the accumulation overflowed.
+	#[inline] pub fn value(&self) -> Option<T> { self.value }
+	/// True if the accumulation overflowed.
+	#[inline] pub fn overflowed(&self) -> bool { self.value.is_none() }
+}
+
+macro_rules! implement_checked {
+	($($index_type:ty,)*) => (
+		$(
+			impl Semigroup for Checked<$index_type> {
+				#[inline] fn is_zero(&self) -> bool { self.value == Some(0) }
+			}
+			impl Monoid for Checked<$index_type> {
+				#[inline] fn zero() -> Self { Checked::new(0) }
+			}
+			impl<'a> AddAssign<&'a Checked<$index_type>> for Checked<$index_type> {
+				#[inline] fn add_assign(&mut self, rhs: &'a Self) {
+					self.value = match (self.value, rhs.value) {
+						(Some(x), Some(y)) => x.checked_add(y),
+						_ => None,
+					};
+				}
+			}
+			impl Neg for Checked<$index_type> {
+				type Output = Checked<$index_type>;
+				#[inline] fn neg(self) -> Self::Output {
+					Checked { value: self.value.and_then(|x| x.checked_neg()) }
+				}
+			}
+			impl Mul<Checked<$index_type>> for Checked<$index_type> {
+				type Output = Checked<$index_type>;
+				#[inline] fn mul(self, other: Checked<$index_type>) -> Self::Output {
+					let value = match (self.value, other.value) {
+						(Some(x), Some(y)) => x.checked_mul(y),
+						_ => None,
+					};
+					Checked { value }
+				}
+			}
+			impl Mul<$index_type> for Checked<$index_type> {
+				type Output = Checked<$index_type>;
+				#[inline] fn mul(self, other: $index_type) -> Self::Output {
+					self * Checked::new(other)
+				}
+			}
+		)*
+	)
+}
+
+implement_checked!(isize, i128, i64, i32, i16, i8,);
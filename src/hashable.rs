@@ -13,8 +13,13 @@
 //! The intent is that types should be able to indicate how this integer is determined, so that general
 //! data types can use a generic hash function, where as more specialized types such as uniformly
 //! distributed integers can perhaps do something simpler (like report their own value).
+//!
+//! Where the choice should depend on the computation rather than the type, a `Partitioner` assigns keys
+//! to workers. Operators like `arrange_by_key_partitioned` and `join_partitioned` accept one, allowing
+//! range partitioning, co-location with an external system's sharding, or a seeded hash that spreads
+//! adversarially chosen keys.
 
-use std::hash::Hasher;
+use std::hash::{Hash, Hasher};
 use std::ops::Deref;
 
 use abomonation::Abomonation;
@@ -177,3 +182,82 @@ impl<T: Unsigned+Copy> From<T> for UnsignedWrapper<T> {
     #[inline]
     fn from(item: T) -> Self { UnsignedWrapper { item } }
 }
+
+/// A rule assigning keys to workers.
+///
+/// Records are sent to the worker indexed by `partition(key)` modulo the number of workers, and equal
+/// keys must receive equal partitions. Each input to an operator that matches keys, like `join`, must
+/// use the same partitioner.
+///
+/// Closures `Fn(&K)->u64` are partitioners, as are the `HashPartitioner`, `SeededPartitioner`, and
+/// `RangePartitioner` types.
+pub trait Partitioner<K>: 'static {
+    /// The partition of `key`.
+    fn partition(&self, key: &K) -> u64;
+}
+
+impl<K, F: Fn(&K)->u64+'static> Partitioner<K> for F {
+    #[inline]
+    fn partition(&self, key: &K) -> u64 { self(key) }
+}
+
+/// Partitions keys by their `hashed()` value, as the default arrangements do.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HashPartitioner;
+
+impl<K: Hashable> Partitioner<K> for HashPartitioner {
+    #[inline]
+    fn partition(&self, key: &K) -> u64 { key.hashed().as_u64() }
+}
+
+/// Partitions keys by a hash mixed with a seed.
+///
+/// Keys that collide under `hashed()`, perhaps by adversarial choice, are unlikely to also collide
+/// under a hash with an unpredictable seed.
+#[derive(Clone, Copy, Debug)]
+pub struct SeededPartitioner {
+    seed: u64,
+}
+
+impl SeededPartitioner {
+    /// Creates a partitioner hashing with `seed`.
+    pub fn new(seed: u64) -> Self { SeededPartitioner { seed } }
+}
+
+impl<K: Hash> Partitioner<K> for SeededPartitioner {
+    #[inline]
+    fn partition(&self, key: &K) -> u64 {
+        let mut h: ::fnv::FnvHasher = Default::default();
+        h.write_u64(self.seed);
+        key.hash(&mut h);
+        h.finish()
+    }
+}
+
+/// Partitions keys into the ranges between consecutive bounds.
+///
+/// Keys less than the first bound are in partition zero, keys at least the first bound and less than
+/// the second are in partition one, and so on. With one fewer bound than workers, each range is sent
+/// to its own worker.
+#[derive(Clone, Debug)]
+pub struct RangePartitioner<K> {
+    bounds: Vec<K>,
+}
+
+impl<K: Ord> RangePartitioner<K> {
+    /// Creates a partitioner from the bounds between ranges, which are sorted if they are not already.
+    pub fn new(mut bounds: Vec<K>) -> Self {
+        bounds.sort();
+        RangePartitioner { bounds }
+    }
+}
+
+impl<K: Ord+'static> Partitioner<K> for RangePartitioner<K> {
+    #[inline]
+    fn partition(&self, key: &K) -> u64 {
+        match self.bounds.binary_search(key) {
+            Ok(index) => (index + 1) as u64,
+            Err(index) => index as u64,
+        }
+    }
+}
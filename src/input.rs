@@ -14,6 +14,7 @@ use timely::dataflow::scopes::ScopeParent;
 use ::Data;
 use ::difference::Semigroup;
 use collection::{Collection, AsCollection};
+use lattice::Bitemporal;
 
 /// Create a new collection and input handle to control the collection.
 pub trait Input : TimelyInput {
@@ -301,6 +302,25 @@ impl<T: Timestamp+Clone, D: Data, R: Semigroup> InputSession<T, D, R> {
 	pub fn close(self) { }
 }
 
+impl<D: Data, R: Semigroup> InputSession<Bitemporal, D, R> {
+
+    /// Adds to the weight of an element as of event time `event`, recorded at the current system time.
+    ///
+    /// The event time may precede the event coordinate of updates already recorded, which allows
+    /// corrections to the past; it must not precede the event coordinate of the session's time,
+    /// which is commonly zero.
+    pub fn update_at_event(&mut self, element: D, event: u64, change: R) {
+        let time = Bitemporal::new(self.time.system, event);
+        self.update_at(element, time, change);
+    }
+
+    /// Advances the system time for future records, retaining the session's event coordinate.
+    pub fn advance_system_to(&mut self, system: u64) {
+        let time = Bitemporal::new(system, self.time.event);
+        self.advance_to(time);
+    }
+}
+
 impl<T: Timestamp+Clone, D: Data, R: Semigroup> Drop for InputSession<T, D, R> {
 	fn drop(&mut self) {
 		self.flush();
@@ -3,6 +3,20 @@
 //! Lattices form the basis of differential dataflow's efficient execution in the presence of
 //! iterative sub-computations. All logical times in differential dataflow must implement the
 //! `Lattice` trait, and all reasoning in operators are done it terms of `Lattice` methods.
+//!
+//! Integers are lattices under their usual total order. As with timely dataflow's timestamps, the
+//! minimum of a signed integer type is zero rather than its most negative value. Composite times
+//! ordered coordinate-wise should use `timely::order::Product`, which is a lattice whenever its
+//! coordinates are. Tuples, `Option`, and other types do not implement timely's `PartialOrder`, but
+//! those whose `Ord` implementation is the intended order can be wrapped in `Total`, which is a lattice
+//! and a timestamp under that total order.
+//!
+//! Traces compact their updates by advancing times with `advance_by`, and `advance_and_consolidate`
+//! combines this with consolidation for runs of `(time, diff)` updates.
+//!
+//! The `Bitemporal` type is a timestamp with both a system time and an event time, for collections
+//! that record corrections to the past. The `Lexicographic` type refines a timestamp with any number
+//! of ordered steps, so that updates at the same time can be ordered, as the `delta` operators require.
 
 use timely::order::PartialOrder;
 
@@ -150,16 +164,72 @@ pub trait Lattice : PartialOrder {
     /// ```
     #[inline]
     fn advance_by(&mut self, frontier: &[Self]) where Self: Sized {
-        if let Some(first) = frontier.get(0) {
-            let mut result = self.join(first);
-            for f in &frontier[1..] {
-                result.meet_assign(&self.join(f));
-            }
-            *self = result;
+        match frontier.len() {
+            0 => { },
+            // A single element frontier, as for totally ordered times, advances by a join.
+            1 => {
+                if !frontier[0].less_equal(self) {
+                    self.join_assign(&frontier[0]);
+                }
+            },
+            // Times in advance of some frontier element are unchanged; otherwise, meet the joins.
+            _ => {
+                if !frontier.iter().any(|f| f.less_equal(self)) {
+                    let mut result = self.join(&frontier[0]);
+                    for f in &frontier[1..] {
+                        result.meet_assign(&self.join(f));
+                    }
+                    *self = result;
+                }
+            },
         }
     }
 }
 
+use difference::Semigroup;
+
+/// Advances the times of `updates` by `frontier`, then sorts and consolidates the updates.
+///
+/// The result is the length of the consolidated prefix of `updates`, and elements beyond it should
+/// be discarded. At every time greater or equal to an element of `frontier`, the consolidated updates
+/// accumulate to the same value as the original updates.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate timely;
+/// # extern crate differential_dataflow;
+/// # use timely::order::Product;
+/// # use differential_dataflow::lattice::advance_and_consolidate;
+/// # fn main() {
+///
+/// let mut updates = vec![(Product::new(3, 7), 1), (Product::new(4, 6), 1), (Product::new(5, 9), -1)];
+/// let frontier = [Product::new(5, 8)];
+///
+/// let count = advance_and_consolidate(&mut updates[..], &frontier[..]);
+/// updates.truncate(count);
+///
+/// assert_eq!(updates, vec![(Product::new(5, 8), 2), (Product::new(5, 9), -1)]);
+/// # }
+/// ```
+pub fn advance_and_consolidate<T: Lattice+Ord, R: Semigroup>(updates: &mut [(T, R)], frontier: &[T]) -> usize {
+    for update in updates.iter_mut() {
+        update.0.advance_by(frontier);
+    }
+    ::consolidation::consolidate_slice(updates)
+}
+
+/// Advances the times of `(data, time)` updates by `frontier`, then sorts and consolidates the updates.
+///
+/// This is `advance_and_consolidate` for updates whose times accompany data, as in the histories that
+/// `reduce` replays, and `updates` is truncated to the consolidated updates.
+pub fn advance_and_consolidate_data<D: Ord, T: Lattice+Ord, R: Semigroup>(updates: &mut Vec<((D, T), R)>, frontier: &[T]) {
+    for update in updates.iter_mut() {
+        (update.0).1.advance_by(frontier);
+    }
+    ::consolidation::consolidate(updates);
+}
+
 use timely::order::Product;
 
 impl<T1: Lattice, T2: Lattice> Lattice for Product<T1, T2> {
@@ -200,5 +270,282 @@ implement_lattice!(u64, 0);
 implement_lattice!(u32, 0);
 implement_lattice!(u16, 0);
 implement_lattice!(u8, 0);
+implement_lattice!(isize, 0);
+implement_lattice!(i128, 0);
+implement_lattice!(i64, 0);
 implement_lattice!(i32, 0);
+implement_lattice!(i16, 0);
+implement_lattice!(i8, 0);
 implement_lattice!((), ());
+
+/// A time with a system coordinate and an event coordinate.
+///
+/// The system coordinate indicates when an update was recorded, and the event coordinate indicates
+/// when the recorded fact took effect. Times are partially ordered by the product order, in which
+/// `(s1, e1)` is less or equal to `(s2, e2)` exactly when `s1 <= s2` and `e1 <= e2`. Consequently,
+/// the accumulation of a collection at time `(s, e)` describes event times up through `e` as known
+/// at system time `s`, and a correction recorded at system time `s` about an earlier event time
+/// `e` is an update at `(s, e)`.
+///
+/// Compaction by `advance_by` with a frontier of system times (for example `(s, 0)`) forgets the
+/// distinctions between system times before `s`, while keeping all event times distinct.
+///
+/// The derived `Ord` implementation is lexicographic, by system and then event coordinate, and is
+/// only used to sort times; it is not the timestamp order.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate timely;
+/// # extern crate differential_dataflow;
+/// # use timely::PartialOrder;
+/// # use differential_dataflow::lattice::{Lattice, Bitemporal};
+/// # fn main() {
+///
+/// let recorded = Bitemporal::new(3, 7);
+/// let corrected = Bitemporal::new(4, 5);
+///
+/// assert!(!recorded.less_equal(&corrected));
+/// assert_eq!(recorded.join(&corrected), Bitemporal::new(4, 7));
+///
+/// // forgetting system times before 4 keeps the event time.
+/// let mut advanced = recorded;
+/// advanced.advance_by(&[Bitemporal::new(4, 0)]);
+/// assert_eq!(advanced, Bitemporal::new(4, 7));
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq, Ord, PartialOrd, Abomonation, Serialize, Deserialize)]
+pub struct Bitemporal {
+    /// The time at which an update was recorded.
+    pub system: u64,
+    /// The time at which the recorded fact takes effect.
+    pub event: u64,
+}
+
+impl Bitemporal {
+    /// Creates a new time from system and event coordinates.
+    pub fn new(system: u64, event: u64) -> Self {
+        Bitemporal { system, event }
+    }
+}
+
+impl PartialOrder for Bitemporal {
+    #[inline]
+    fn less_equal(&self, other: &Self) -> bool {
+        self.system <= other.system && self.event <= other.event
+    }
+}
+
+use std::hash::Hash;
+
+use timely::progress::{PathSummary, Timestamp};
+use timely::progress::timestamp::Refines;
+
+use ExchangeData;
+
+// A `Bitemporal` summary advances each coordinate by its own amount.
+impl PathSummary<Bitemporal> for Bitemporal {
+    #[inline]
+    fn results_in(&self, timestamp: &Bitemporal) -> Option<Bitemporal> {
+        match (timestamp.system.checked_add(self.system), timestamp.event.checked_add(self.event)) {
+            (Some(system), Some(event)) => Some(Bitemporal::new(system, event)),
+            _ => None,
+        }
+    }
+    #[inline]
+    fn followed_by(&self, other: &Bitemporal) -> Option<Bitemporal> {
+        self.results_in(other)
+    }
+}
+
+impl Timestamp for Bitemporal {
+    type Summary = Bitemporal;
+}
+
+impl Lattice for Bitemporal {
+    #[inline]
+    fn minimum() -> Self { Bitemporal::new(0, 0) }
+    #[inline]
+    fn join(&self, other: &Self) -> Self {
+        Bitemporal::new(::std::cmp::max(self.system, other.system), ::std::cmp::max(self.event, other.event))
+    }
+    #[inline]
+    fn meet(&self, other: &Self) -> Self {
+        Bitemporal::new(::std::cmp::min(self.system, other.system), ::std::cmp::min(self.event, other.event))
+    }
+}
+
+/// A totally ordered type, as a lattice and timestamp under its `Ord` implementation.
+///
+/// Tuples, `Option`, and other types whose `Ord` implementation is a total order become times when
+/// wrapped in `Total`; tuples are then ordered lexicographically, whereas `Product` orders coordinates
+/// independently. The join and meet are the maximum and minimum.
+///
+/// Timely dataflow starts computations at the default timestamp, and so the default value of `T` must
+/// be its least value. This holds for unsigned integers, `bool`, `Option` (whose default is `None`),
+/// `String`, `Vec`, and tuples of such types, but not for signed integers or `std::cmp::Reverse`.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate timely;
+/// # extern crate differential_dataflow;
+/// # use timely::PartialOrder;
+/// # use differential_dataflow::lattice::{Lattice, Total};
+/// # fn main() {
+///
+/// let epoch = Total((3u64, 7u32));
+/// let next = Total((4u64, 0u32));
+///
+/// assert!(epoch.less_equal(&next));
+/// assert_eq!(epoch.join(&next), next);
+/// assert_eq!(<Total<Option<u64>> as Lattice>::minimum(), Total(None));
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq, Ord, PartialOrd, Abomonation, Serialize, Deserialize)]
+pub struct Total<T>(pub T);
+
+impl<T: Ord> PartialOrder for Total<T> {
+    #[inline]
+    fn less_equal(&self, other: &Self) -> bool {
+        self.0 <= other.0
+    }
+}
+
+// Times are only introduced as inputs advance, and so summaries are trivial.
+impl<T: ExchangeData+Default+Hash> PathSummary<Total<T>> for () {
+    fn results_in(&self, timestamp: &Total<T>) -> Option<Total<T>> {
+        Some(timestamp.clone())
+    }
+    fn followed_by(&self, other: &Self) -> Option<Self> {
+        Some(other.clone())
+    }
+}
+
+impl<T: ExchangeData+Default+Hash> Timestamp for Total<T> {
+    type Summary = ();
+}
+
+impl<T: Ord+Clone+Default> Lattice for Total<T> {
+    #[inline]
+    fn minimum() -> Self { Total(T::default()) }
+    #[inline]
+    fn join(&self, other: &Self) -> Self { ::std::cmp::max(self, other).clone() }
+    #[inline]
+    fn meet(&self, other: &Self) -> Self { ::std::cmp::min(self, other).clone() }
+}
+
+/// A timestamp refined by a totally ordered step, with steps at the same time ordered by index.
+///
+/// Two times are ordered if their `time` fields are equal and their steps are ordered, or if their
+/// `time` fields are distinct and ordered. A delta query over relations `0 .. n` can introduce
+/// the updates of relation `i` at step `i`, so that the changes to relation `i` see the changes to
+/// earlier relations at the same time, but not those to later relations.
+///
+/// The join of two times has as its `time` the join of their `time` fields, and as its step the
+/// largest step of those times with that `time` field, or zero if there are none. The meet is
+/// defined symmetrically, with the largest step in place of zero.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate timely;
+/// # extern crate differential_dataflow;
+/// # use timely::PartialOrder;
+/// # use differential_dataflow::lattice::{Lattice, Lexicographic};
+/// # fn main() {
+///
+/// let first = Lexicographic::new(3u64, 0usize);
+/// let second = Lexicographic::new(3u64, 2usize);
+/// let later = Lexicographic::new(4u64, 1usize);
+///
+/// assert!(first.less_equal(&second));
+/// assert!(second.less_equal(&later));
+/// assert_eq!(second.join(&later), later);
+/// # }
+/// ```
+#[derive(Debug, Hash, Default, Clone, Eq, PartialEq, Ord, PartialOrd, Abomonation, Serialize, Deserialize)]
+pub struct Lexicographic<T, S> {
+    /// The refined time.
+    pub time: T,
+    /// The step within `time`.
+    pub step: S,
+}
+
+impl<T, S> Lexicographic<T, S> {
+    /// The refinement of `time` at `step`.
+    pub fn new(time: T, step: S) -> Self { Lexicographic { time, step } }
+}
+
+impl<T: PartialOrder, S: Ord> PartialOrder for Lexicographic<T, S> {
+    fn less_equal(&self, other: &Self) -> bool {
+        if self.time.eq(&other.time) {
+            self.step <= other.step
+        }
+        else {
+            self.time.less_equal(&other.time)
+        }
+    }
+}
+
+macro_rules! implement_lexicographic {
+    ($step:ty) => (
+        // Times only enter and leave a scope with `Lexicographic` timestamps, and so summaries are trivial.
+        impl<T: Timestamp> PathSummary<Lexicographic<T, $step>> for () {
+            fn results_in(&self, timestamp: &Lexicographic<T, $step>) -> Option<Lexicographic<T, $step>> {
+                Some(timestamp.clone())
+            }
+            fn followed_by(&self, other: &Self) -> Option<Self> {
+                Some(other.clone())
+            }
+        }
+
+        impl<T: Timestamp> Timestamp for Lexicographic<T, $step> {
+            type Summary = ();
+        }
+
+        impl<T: Timestamp> Refines<T> for Lexicographic<T, $step> {
+            fn to_inner(other: T) -> Self {
+                Lexicographic::new(other, 0)
+            }
+            fn to_outer(self: Lexicographic<T, $step>) -> T {
+                self.time
+            }
+            fn summarize(_path: ()) -> <T as Timestamp>::Summary {
+                Default::default()
+            }
+        }
+
+        impl<T: Lattice> Lattice for Lexicographic<T, $step> {
+            fn minimum() -> Self { Lexicographic::new(T::minimum(), 0) }
+            fn join(&self, other: &Self) -> Self {
+                let time = self.time.join(&other.time);
+                let mut step = 0;
+                if time == self.time {
+                    step = ::std::cmp::max(step, self.step);
+                }
+                if time == other.time {
+                    step = ::std::cmp::max(step, other.step);
+                }
+                Lexicographic { time, step }
+            }
+            fn meet(&self, other: &Self) -> Self {
+                let time = self.time.meet(&other.time);
+                let mut step = <$step>::max_value();
+                if time == self.time {
+                    step = ::std::cmp::min(step, self.step);
+                }
+                if time == other.time {
+                    step = ::std::cmp::min(step, other.step);
+                }
+                Lexicographic { time, step }
+            }
+        }
+    )
+}
+
+implement_lexicographic!(usize);
+implement_lexicographic!(u64);
+implement_lexicographic!(u32);
+implement_lexicographic!(u16);
+implement_lexicographic!(u8);
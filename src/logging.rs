@@ -27,6 +27,10 @@ pub enum DifferentialEvent {
     Drop(DropEvent),
     /// A merge failed to complete in time.
     MergeShortfall(MergeShortfall),
+    /// Updates fed back in one round of an instrumented iteration.
+    Iteration(IterationEvent),
+    /// Updates in one round of an instrumented iteration that undo those of the prior round.
+    Oscillation(OscillationEvent),
 }
 
 /// Either the start or end of a merge event.
@@ -81,3 +85,31 @@ pub struct MergeShortfall {
 }
 
 impl From<MergeShortfall> for DifferentialEvent { fn from(e: MergeShortfall) -> Self { DifferentialEvent::MergeShortfall(e) } }
+
+/// Updates fed back in one round of an instrumented iteration.
+#[derive(Debug, Clone, Abomonation, Ord, PartialOrd, Eq, PartialEq)]
+pub struct IterationEvent {
+    /// Name of the instrumented variable.
+    pub name: String,
+    /// The round into which updates were fed back.
+    pub round: u64,
+    /// Number of distinct records updated.
+    pub updates: usize,
+}
+
+impl From<IterationEvent> for DifferentialEvent { fn from(e: IterationEvent) -> Self { DifferentialEvent::Iteration(e) } }
+
+/// Updates in one round of an instrumented iteration that undo those of the prior round.
+#[derive(Debug, Clone, Abomonation, Ord, PartialOrd, Eq, PartialEq)]
+pub struct OscillationEvent {
+    /// Name of the instrumented variable.
+    pub name: String,
+    /// The round into which updates were fed back.
+    pub round: u64,
+    /// Number of records whose update cancels their update in the prior round.
+    pub oscillating: usize,
+    /// Formatted examples of oscillating records.
+    pub sample: Vec<String>,
+}
+
+impl From<OscillationEvent> for DifferentialEvent { fn from(e: OscillationEvent) -> Self { DifferentialEvent::Oscillation(e) } }
@@ -11,6 +11,7 @@ use timely::progress::Timestamp;
 use timely::dataflow::operators::CapabilitySet;
 
 use lattice::Lattice;
+use difference::Semigroup;
 use trace::{Trace, TraceReader, Batch, BatchReader, Cursor};
 
 use trace::wrappers::rc::TraceBox;
@@ -403,6 +404,7 @@ where
     where
         G: Scope<Timestamp=Tr::Time>,
         Tr::Time: Timestamp+ Lattice+Ord+Clone+'static,
+        Tr::R: Semigroup,
         Tr: TraceReader,
     {
         // This frontier describes our only guarantee on the compaction frontier.
@@ -415,6 +417,7 @@ where
     where
         G: Scope<Timestamp=Tr::Time>,
         Tr::Time: Timestamp+ Lattice+Ord+Clone+'static,
+        Tr::R: Semigroup,
         Tr: TraceReader,
     {
         let trace = self.clone();
@@ -20,7 +20,7 @@
 use std::rc::Rc;
 use std::default::Default;
 
-use timely::dataflow::operators::{Enter, Map};
+use timely::dataflow::operators::{Enter, Map, Broadcast};
 use timely::order::{PartialOrder, TotalOrder};
 use timely::dataflow::{Scope, Stream};
 use timely::dataflow::operators::generic::Operator;
@@ -32,6 +32,7 @@ use timely::dataflow::operators::Capability;
 use timely_sort::Unsigned;
 
 use ::{Data, ExchangeData, Collection, AsCollection, Hashable};
+use hashable::Partitioner;
 use ::difference::Semigroup;
 use lattice::Lattice;
 use trace::{Trace, TraceReader, Batch, BatchReader, Batcher, Cursor};
@@ -691,6 +692,38 @@ where G::Timestamp: Lattice+Ord {
     /// This trace is current for all times completed by the output stream, which can be used to
     /// safely identify the stable times and values in the trace.
     fn arrange_by_key(&self) -> Arranged<G, TraceAgent<DefaultValTrace<K, V, G::Timestamp, R>>>;
+
+    /// Arranges a collection of `(Key, Val)` records by `Key`, assigning keys to workers with `partitioner`.
+    ///
+    /// Operators that match keys across arrangements, like `join_core`, require that each arrangement
+    /// used the same partitioner.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate timely;
+    /// extern crate differential_dataflow;
+    ///
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::hashable::RangePartitioner;
+    /// use differential_dataflow::operators::arrange::ArrangeByKey;
+    ///
+    /// fn main() {
+    ///     ::timely::example(|scope| {
+    ///         // keys below 100 and the remaining keys are arranged at different workers.
+    ///         scope.new_collection_from(vec![(1u32, 'a'), (150u32, 'b')]).1
+    ///              .arrange_by_key_partitioned(RangePartitioner::new(vec![100u32]));
+    ///     });
+    /// }
+    /// ```
+    fn arrange_by_key_partitioned<P: Partitioner<K>>(&self, partitioner: P) -> Arranged<G, TraceAgent<DefaultValTrace<K, V, G::Timestamp, R>>>;
+
+    /// Arranges a collection of `(Key, Val)` records by `Key`, replicating the arrangement at every worker.
+    ///
+    /// Each worker maintains all of the collection, and so this is only appropriate for small collections,
+    /// for example dimension tables. In exchange, an arrangement at any worker can be joined with it
+    /// without moving its data; see `Join::join_broadcast`.
+    fn arrange_broadcast(&self) -> Arranged<G, TraceAgent<DefaultValTrace<K, V, G::Timestamp, R>>>;
 }
 
 impl<G: Scope, K: ExchangeData+Hashable, V: ExchangeData, R: ExchangeData+Semigroup> ArrangeByKey<G, K, V, R> for Collection<G, (K,V), R>
@@ -700,6 +733,18 @@ where
     fn arrange_by_key(&self) -> Arranged<G, TraceAgent<DefaultValTrace<K, V, G::Timestamp, R>>> {
         self.arrange()
     }
+
+    fn arrange_by_key_partitioned<P: Partitioner<K>>(&self, partitioner: P) -> Arranged<G, TraceAgent<DefaultValTrace<K, V, G::Timestamp, R>>> {
+        let exchange = Exchange::new(move |update: &((K,V),G::Timestamp,R)| partitioner.partition(&(update.0).0));
+        self.arrange_core(exchange, "ArrangePartitioned")
+    }
+
+    fn arrange_broadcast(&self) -> Arranged<G, TraceAgent<DefaultValTrace<K, V, G::Timestamp, R>>> {
+        self.inner
+            .broadcast()
+            .as_collection()
+            .arrange_core(Pipeline, "ArrangeBroadcast")
+    }
 }
 
 /// Arranges something as `(Key, ())` pairs according to a type `T` of trace.
@@ -717,6 +762,12 @@ where
     /// This trace is current for all times complete in the output stream, which can be used to safely
     /// identify the stable times and values in the trace.
     fn arrange_by_self(&self) -> Arranged<G, TraceAgent<DefaultKeyTrace<K, G::Timestamp, R>>>;
+
+    /// Arranges a collection of `Key` records by `Key`, assigning keys to workers with `partitioner`.
+    ///
+    /// Operators that match keys across arrangements, like `join_core`, require that each arrangement
+    /// used the same partitioner.
+    fn arrange_by_self_partitioned<P: Partitioner<K>>(&self, partitioner: P) -> Arranged<G, TraceAgent<DefaultKeyTrace<K, G::Timestamp, R>>>;
 }
 
 
@@ -728,4 +779,10 @@ where
         self.map(|k| (k, ()))
             .arrange()
     }
+
+    fn arrange_by_self_partitioned<P: Partitioner<K>>(&self, partitioner: P) -> Arranged<G, TraceAgent<DefaultKeyTrace<K, G::Timestamp, R>>> {
+        let exchange = Exchange::new(move |update: &((K,()),G::Timestamp,R)| partitioner.partition(&(update.0).0));
+        self.map(|k| (k, ()))
+            .arrange_core(exchange, "ArrangePartitioned")
+    }
 }
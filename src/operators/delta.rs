@@ -0,0 +1,305 @@
+//! Multiway joins maintained as delta queries.
+//!
+//! A delta query maintains a join of several relations by joining the changes to each relation with
+//! the other relations, one at a time, using arrangements of the input relations but never of the
+//! intermediate results. For each relation the other relations are visited in an order where each
+//! shares a variable with those already joined, and each is looked up with the variables already
+//! bound as its key.
+//!
+//! Changes to the relations at the same time must be joined with care, so that each combination of
+//! changes contributes exactly once. The delta queries run in a scope with `Lexicographic` timestamps,
+//! where the updates of each relation are introduced at the step of its index. The changes to a relation
+//! then see the relations before it in order at the same time, and the relations after it only at
+//! strictly earlier times.
+//!
+//! Relations may also be supplied as arrangements keyed by some of their columns, with the other columns
+//! as values. Lookups keyed by exactly those columns use the supplied arrangement rather than arranging
+//! the relation again, which lets several joins share the arrangements of their common relations.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate timely;
+//! extern crate differential_dataflow;
+//!
+//! use differential_dataflow::input::Input;
+//! use differential_dataflow::operators::delta::DeltaJoin;
+//!
+//! fn main() {
+//!     ::timely::example(|scope| {
+//!
+//!         let edges = scope.new_collection_from(vec![vec![0u32, 1], vec![1, 2], vec![0, 2]]).1;
+//!
+//!         // triangles(a,b,c) := edges(a,b), edges(b,c), edges(a,c)
+//!         let triangles =
+//!         DeltaJoin::new()
+//!             .relation(&edges, &[0, 1])
+//!             .relation(&edges, &[1, 2])
+//!             .relation(&edges, &[0, 2])
+//!             .join()
+//!             .expect("well-formed join");
+//!
+//!         triangles.assert_eq(&scope.new_collection_from(vec![vec![0u32, 1, 2]]).1);
+//!     });
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::Mul;
+
+use timely::dataflow::Scope;
+use timely::dataflow::scopes::ScopeParent;
+
+use ::{ExchangeData, Collection};
+use ::difference::Monoid;
+use lattice::{Lattice, Lexicographic};
+use operators::arrange::{Arranged, ArrangeByKey, TraceAgent};
+use operators::half_join::{half_join, ProbeAs};
+use trace::implementations::ord::OrdValSpine;
+
+/// The number of output records each lookup produces before yielding.
+const FUEL: usize = 1_000_000;
+
+/// A relation arranged by the values of some of its columns, with the values of the other columns.
+pub type DeltaIndex<G, V, R> = Arranged<G, TraceAgent<OrdValSpine<Vec<V>, Vec<V>, <G as ScopeParent>::Timestamp, R>>>;
+
+/// A join of relations whose columns are bound to variables, maintained as a delta query.
+///
+/// Each relation is a collection of rows, and its columns are bound to variables identified by
+/// integers. The join contains a row for each assignment of values to the variables consistent with
+/// a row of every relation, indexed by variable.
+pub struct DeltaJoin<G: Scope, V: ExchangeData, R: Monoid> {
+    relations: Vec<(Collection<G, Vec<V>, R>, Vec<usize>)>,
+    arrangements: Vec<(usize, Vec<usize>, DeltaIndex<G, V, R>)>,
+}
+
+impl<G, V, R> DeltaJoin<G, V, R>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    V: ExchangeData+Hash,
+    R: ExchangeData+Monoid+Mul<Output=R>,
+{
+    /// Creates a join of no relations.
+    pub fn new() -> Self {
+        DeltaJoin { relations: Vec::new(), arrangements: Vec::new() }
+    }
+
+    /// Adds a relation, binding each of its columns to the corresponding variable.
+    ///
+    /// The same variable may bind several columns, which restricts the relation to rows whose values
+    /// in those columns are equal.
+    pub fn relation(&mut self, collection: &Collection<G, Vec<V>, R>, variables: &[usize]) -> &mut Self {
+        self.relations.push((collection.clone(), variables.to_vec()));
+        self
+    }
+
+    /// Adds a relation supplied as an arrangement, binding each of its columns to the corresponding variable.
+    ///
+    /// The arrangement is keyed by the values of `key_columns`, which must be strictly increasing, and its
+    /// values are the values of the other columns in order. Lookups into the relation keyed by exactly
+    /// `key_columns` use the arrangement, and other lookups arrange the relation's rows as for `relation`.
+    pub fn relation_arranged(&mut self, arranged: &DeltaIndex<G, V, R>, key_columns: &[usize], variables: &[usize]) -> &mut Self {
+        let keys = key_columns.to_vec();
+        let arity = variables.len();
+        let rows = arranged.as_collection(move |key, val| {
+            let mut vals = val.iter();
+            (0 .. arity)
+                .map(|c| match keys.iter().position(|&k| k == c) {
+                    Some(p) => key[p].clone(),
+                    None => vals.next().expect("too few value columns").clone(),
+                })
+                .collect::<Vec<_>>()
+        });
+        self.arrangements.push((self.relations.len(), key_columns.to_vec(), arranged.clone()));
+        self.relations.push((rows, variables.to_vec()));
+        self
+    }
+
+    /// The order in which the other relations are joined with changes to each relation.
+    ///
+    /// Each relation is preceded by one sharing a variable, and among those relations the one with the
+    /// most bound columns is joined first. An error is returned if the relations do not form a connected
+    /// join graph, if some variable is bound by no relation, or if the key columns of an arranged
+    /// relation are not strictly increasing columns of the relation.
+    pub fn plan(&self) -> Result<Vec<Vec<usize>>, String> {
+
+        if self.relations.is_empty() {
+            return Err("a join must have at least one relation".to_owned());
+        }
+
+        for &(relation, ref key_columns, _) in self.arrangements.iter() {
+            let arity = self.relations[relation].1.len();
+            if key_columns.windows(2).any(|w| w[0] >= w[1]) || key_columns.iter().any(|&c| c >= arity) {
+                return Err(format!("key columns {:?} of relation {} are not strictly increasing columns", key_columns, relation));
+            }
+        }
+
+        let variables = self.relations.iter().flat_map(|x| x.1.iter().cloned()).max().map(|x| x + 1).unwrap_or(0);
+        for variable in 0 .. variables {
+            if !self.relations.iter().any(|x| x.1.contains(&variable)) {
+                return Err(format!("variable {} is bound by no relation", variable));
+            }
+        }
+
+        let mut plans = Vec::new();
+        for delta in 0 .. self.relations.len() {
+            let mut bound = self.relations[delta].1.clone();
+            let mut remaining = (0 .. self.relations.len()).filter(|&x| x != delta).collect::<Vec<_>>();
+            let mut order = Vec::new();
+            while !remaining.is_empty() {
+                let (position, shared) =
+                remaining
+                    .iter()
+                    .map(|&index| self.relations[index].1.iter().filter(|v| bound.contains(v)).count())
+                    .enumerate()
+                    .max_by_key(|&(position, shared)| (shared, ::std::cmp::Reverse(position)))
+                    .unwrap();
+                if shared == 0 {
+                    return Err(format!("relations {:?} share no variables with relation {} and those joined with it", remaining, delta));
+                }
+                let next = remaining.remove(position);
+                bound.extend(self.relations[next].1.iter().cloned());
+                order.push(next);
+            }
+            plans.push(order);
+        }
+        Ok(plans)
+    }
+
+    /// Forms the join, as the sum of the delta queries for each relation.
+    ///
+    /// Each relation is arranged once for each set of columns used as a key, unless it was supplied with
+    /// an arrangement by those columns, and these arrangements are shared by the delta queries. The results of the delta queries are not arranged.
+    pub fn join(&self) -> Result<Collection<G, Vec<V>, R>, String> {
+
+        let plans = self.plan()?;
+        let variables = self.relations.iter().flat_map(|x| x.1.iter().cloned()).max().map(|x| x + 1).unwrap_or(0);
+
+        // Determine the key columns of each lookup, and arrange each relation by its distinct keys.
+        let mut lookups = Vec::new();
+        let mut indices = HashMap::new();
+        for &(relation, ref key_columns, ref arranged) in self.arrangements.iter() {
+            indices.insert((relation, key_columns.clone()), arranged.clone());
+        }
+        for (delta, order) in plans.iter().enumerate() {
+            let mut bound = distinct(&self.relations[delta].1);
+            let mut steps = Vec::new();
+            for &next in order.iter() {
+                let columns = &self.relations[next].1;
+                let key_columns = (0 .. columns.len()).filter(|&c| bound.contains(&columns[c])).collect::<Vec<_>>();
+                let val_columns = (0 .. columns.len()).filter(|&c| !bound.contains(&columns[c])).collect::<Vec<_>>();
+                indices
+                    .entry((next, key_columns.clone()))
+                    .or_insert_with(|| {
+                        let keys = key_columns.clone();
+                        let vals = val_columns.clone();
+                        self.relations[next].0
+                            .map(move |row| (keys.iter().map(|&c| row[c].clone()).collect::<Vec<_>>(), vals.iter().map(|&c| row[c].clone()).collect::<Vec<_>>()))
+                            .arrange_by_key()
+                    });
+
+                // The key is read from the positions of bound variables in the prefix, and
+                // the values extend the prefix with newly bound variables, or are checked
+                // against values already bound by other columns of the same row.
+                let key_positions = key_columns.iter().map(|&c| position(&bound, columns[c])).collect::<Vec<_>>();
+                let mut extensions = Vec::new();
+                for (index, &c) in val_columns.iter().enumerate() {
+                    match val_columns[.. index].iter().position(|&c2| columns[c2] == columns[c]) {
+                        Some(earlier) => { extensions.push(Some(earlier)); },
+                        None => { extensions.push(None); bound.push(columns[c]); },
+                    }
+                }
+                steps.push((next, key_columns, key_positions, extensions));
+            }
+            let output = (0 .. variables).map(|v| position(&bound, v)).collect::<Vec<_>>();
+            lookups.push((steps, output));
+        }
+
+        let mut scope = self.relations[0].0.scope();
+        let result = scope.scoped::<Lexicographic<G::Timestamp, usize>,_,_>("DeltaJoin", |inner| {
+
+            let mut results = Vec::new();
+            for (delta, (steps, output)) in lookups.into_iter().enumerate() {
+
+                // Changes to the relation, restricted to rows consistent in repeated variables.
+                let columns = self.relations[delta].1.clone();
+                let first = distinct(&columns).into_iter().map(|v| position(&columns, v)).collect::<Vec<_>>();
+                let mut prefixes =
+                self.relations[delta].0
+                    .enter(inner)
+                    .delay(move |time| Lexicographic::new(time.time.clone(), delta))
+                    .filter(move |row| (0 .. columns.len()).all(|c| row[c] == row[position(&columns, columns[c])]))
+                    .map(move |row| first.iter().map(|&c| row[c].clone()).collect::<Vec<_>>());
+
+                for (next, key_columns, key_positions, extensions) in steps.into_iter() {
+                    // Relations at steps before `delta` are visible at the same time, and those after only strictly before.
+                    let index = indices[&(next, key_columns)].enter_at(inner, move |_,_,t| Lexicographic::new(t.clone(), next));
+                    prefixes =
+                    half_join(
+                        &prefixes.map(move |prefix| (key_positions.iter().map(|&p| prefix[p].clone()).collect::<Vec<_>>(), prefix)),
+                        index,
+                        ProbeAs::Time,
+                        FUEL,
+                        move |_key, prefix, vals| {
+                            let mut extended = prefix.clone();
+                            for (index, extension) in extensions.iter().enumerate() {
+                                match *extension {
+                                    Some(earlier) => if vals[earlier] != vals[index] { return None; },
+                                    None => extended.push(vals[index].clone()),
+                                }
+                            }
+                            Some(extended)
+                        },
+                    )
+                    .flat_map(|extended| extended);
+                }
+
+                results.push(prefixes.map(move |prefix| output.iter().map(|&p| prefix[p].clone()).collect::<Vec<_>>()).leave());
+            }
+
+            let mut result = results.pop().expect("at least one relation");
+            for other in results.iter() {
+                result = result.concat(other);
+            }
+            result
+        });
+
+        Ok(result)
+    }
+}
+
+impl<G, V, R> Default for DeltaJoin<G, V, R>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    V: ExchangeData+Hash,
+    R: ExchangeData+Monoid+Mul<Output=R>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The distinct elements of `list`, in order of their first occurrence.
+///
+/// Lists of variables may repeat a variable, and planners bind each distinct variable once.
+pub fn distinct(list: &[usize]) -> Vec<usize> {
+    let mut result = Vec::new();
+    for &x in list.iter() {
+        if !result.contains(&x) {
+            result.push(x);
+        }
+    }
+    result
+}
+
+/// The position of the first occurrence of `item` in `list`.
+///
+/// # Panics
+///
+/// Panics if `item` does not occur in `list`.
+pub fn position(list: &[usize], item: usize) -> usize {
+    list.iter().position(|&x| x == item).expect("item not found")
+}
@@ -0,0 +1,259 @@
+//! Match a stream of updates against an arrangement, reacting only to the stream.
+//!
+//! A half join matches each update `((key, val), time, diff)` of a collection against the values of
+//! `key` in an arrangement, but unlike `join` it does not respond to changes in the arrangement. It is
+//! the building block of delta queries, where each input's changes are looked up in the other inputs,
+//! and of lookups against slowly changing reference data.
+//!
+//! The arrangement can be probed in one of two ways, described by `ProbeAs`. Probing as of the time
+//! of each update waits until the arrangement is complete through that time, and produces an output
+//! that depends only on the times of the input updates. Probing as of the arrangement's frontier uses
+//! whatever the arrangement has received when the update is processed, without waiting.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate timely;
+//! extern crate differential_dataflow;
+//!
+//! use differential_dataflow::input::Input;
+//! use differential_dataflow::operators::arrange::ArrangeByKey;
+//! use differential_dataflow::operators::half_join::{half_join, ProbeAs};
+//!
+//! fn main() {
+//!     ::timely::example(|scope| {
+//!
+//!         let names = scope.new_collection_from(vec![(0, "zero"), (1, "one")]).1.arrange_by_key();
+//!         let numbers = scope.new_collection_from(vec![(0, 10), (1, 11), (2, 12)]).1;
+//!
+//!         half_join(&numbers, names, ProbeAs::Time, 1_000, |_key, &number, &name| (number, name))
+//!             .assert_eq(&scope.new_collection_from(vec![(10, "zero"), (11, "one")]).1);
+//!     });
+//! }
+//! ```
+
+use std::ops::Mul;
+
+use timely::PartialOrder;
+use timely::dataflow::Scope;
+use timely::dataflow::channels::pact::{Pipeline, Exchange};
+use timely::dataflow::operators::{Operator, Capability};
+use timely::progress::frontier::Antichain;
+
+use ::{Data, ExchangeData, Collection, AsCollection, Hashable};
+use hashable::{Partitioner, HashPartitioner};
+use ::difference::Semigroup;
+use lattice::Lattice;
+use operators::arrange::Arranged;
+use trace::{Cursor, TraceReader, BatchReader};
+
+/// The arrangement contents against which a half join matches an update.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ProbeAs {
+    /// The arrangement accumulated at the time of the update.
+    ///
+    /// Updates are held until the arrangement is complete through their times, and the output is the
+    /// same however the inputs are batched.
+    Time,
+    /// The arrangement accumulated through its frontier when the update is processed.
+    ///
+    /// Updates are matched as soon as they arrive, against all updates the arrangement has received,
+    /// and later changes to the arrangement are not reflected in the output.
+    Frontier,
+}
+
+/// Updates received together, which are matched in order of key.
+struct Pending<K, V, T, R> {
+    capability: Capability<T>,
+    updates: Vec<((K, V), T, R)>,
+    /// The number of updates already matched or deferred.
+    position: usize,
+    /// Updates whose times the arrangement is not yet complete through.
+    deferred: Vec<((K, V), T, R)>,
+}
+
+/// Matches each update of `stream` against the values of its key in `arrangement`.
+///
+/// For each update `((key, val), time, diff)` and each value `val2` of `key` in the arrangement, with
+/// accumulated difference `diff2` as described by `probe`, the output contains `output_func(key, val,
+/// val2)` at `time` with difference `diff * diff2`.
+///
+/// The operator produces roughly `fuel` output records each time it is scheduled, and reschedules
+/// itself if work remains, so that updates with many matches do not block the worker. All matches of
+/// a single update are produced together.
+///
+/// Updates are exchanged by the `hashed()` value of their key, and so the arrangement must be partitioned
+/// the same way, as by `arrange_by_key`. Arrangements formed with another partitioner should be probed
+/// with `half_join_partitioned`, or updates will be looked up at workers that do not hold their keys.
+pub fn half_join<G, K, V, R, Tr, S, DOut>(
+    stream: &Collection<G, (K, V), R>,
+    arrangement: Arranged<G, Tr>,
+    probe: ProbeAs,
+    fuel: usize,
+    output_func: S,
+) -> Collection<G, DOut, <R as Mul<Tr::R>>::Output>
+where
+    G: Scope,
+    G::Timestamp: Lattice,
+    K: ExchangeData+Hashable,
+    V: ExchangeData,
+    R: ExchangeData+Semigroup+Mul<Tr::R>,
+    <R as Mul<Tr::R>>::Output: Semigroup,
+    Tr: TraceReader<Key=K, Time=G::Timestamp>+Clone+'static,
+    Tr::Batch: BatchReader<K, Tr::Val, G::Timestamp, Tr::R>,
+    Tr::Cursor: Cursor<K, Tr::Val, G::Timestamp, Tr::R>,
+    Tr::R: Semigroup,
+    DOut: Data,
+    S: FnMut(&K, &V, &Tr::Val)->DOut+'static,
+{
+    half_join_partitioned(stream, arrangement, HashPartitioner, probe, fuel, output_func)
+}
+
+/// As `half_join`, exchanging updates by `partitioner`.
+///
+/// The arrangement must have been formed with the same partitioner, for example by
+/// `arrange_by_key_partitioned`.
+pub fn half_join_partitioned<G, K, V, R, Tr, P, S, DOut>(
+    stream: &Collection<G, (K, V), R>,
+    arrangement: Arranged<G, Tr>,
+    partitioner: P,
+    probe: ProbeAs,
+    fuel: usize,
+    mut output_func: S,
+) -> Collection<G, DOut, <R as Mul<Tr::R>>::Output>
+where
+    G: Scope,
+    G::Timestamp: Lattice,
+    K: ExchangeData,
+    V: ExchangeData,
+    R: ExchangeData+Semigroup+Mul<Tr::R>,
+    <R as Mul<Tr::R>>::Output: Semigroup,
+    Tr: TraceReader<Key=K, Time=G::Timestamp>+Clone+'static,
+    Tr::Batch: BatchReader<K, Tr::Val, G::Timestamp, Tr::R>,
+    Tr::Cursor: Cursor<K, Tr::Val, G::Timestamp, Tr::R>,
+    Tr::R: Semigroup,
+    P: Partitioner<K>,
+    DOut: Data,
+    S: FnMut(&K, &V, &Tr::Val)->DOut+'static,
+{
+    let mut trace = Some(arrangement.trace);
+    let mut stash: Vec<Pending<K, V, G::Timestamp, R>> = Vec::new();
+
+    let mut buffer1 = Vec::new();
+    let mut buffer2 = Vec::new();
+
+    let exchange = Exchange::new(move |update: &((K,V),G::Timestamp,R)| partitioner.partition(&(update.0).0));
+
+    stream.inner.binary_frontier(&arrangement.stream, exchange, Pipeline, "HalfJoin", move |_capability, info| {
+
+        use timely::scheduling::Activator;
+        let activations = stream.scope().activations().clone();
+        let activator = Activator::new(&info.address[..], activations);
+
+        move |input1, input2, output| {
+
+            // drain the first input, stashing updates in order of key.
+            input1.for_each(|capability, data| {
+                data.swap(&mut buffer1);
+                let mut updates = buffer1.drain(..).collect::<Vec<_>>();
+                updates.sort_by(|x,y| (x.0).0.cmp(&(y.0).0));
+                stash.push(Pending {
+                    capability: capability.retain(),
+                    updates,
+                    position: 0,
+                    deferred: Vec::new(),
+                });
+            });
+
+            // advance the `distinguish_since` frontier to allow all merges.
+            input2.for_each(|_, batches| {
+                batches.swap(&mut buffer2);
+                for batch in buffer2.drain(..) {
+                    if let Some(ref mut trace) = trace {
+                        trace.distinguish_since(batch.upper());
+                    }
+                }
+            });
+
+            if let Some(ref mut trace) = trace {
+
+                let frontier = input2.frontier();
+                let mut fuel = fuel as isize;
+
+                for pending in stash.iter_mut() {
+
+                    // If the arrangement is incomplete at the capability, it is incomplete at all updates.
+                    if fuel <= 0 || (probe == ProbeAs::Time && frontier.less_equal(pending.capability.time())) {
+                        continue;
+                    }
+
+                    let mut session = output.session(&pending.capability);
+                    let (mut cursor, storage) = trace.cursor();
+
+                    while pending.position < pending.updates.len() && fuel > 0 {
+                        let ((ref key, ref val), ref time, ref diff) = pending.updates[pending.position];
+                        if probe == ProbeAs::Frontier || !frontier.less_equal(time) {
+                            cursor.seek_key(&storage, key);
+                            if cursor.get_key(&storage) == Some(key) {
+                                while let Some(val2) = cursor.get_val(&storage) {
+                                    let mut count: Option<Tr::R> = None;
+                                    cursor.map_times(&storage, |t, d| {
+                                        if probe == ProbeAs::Frontier || t.less_equal(time) {
+                                            match count {
+                                                Some(ref mut count) => { *count += d; },
+                                                None => { count = Some(d.clone()); },
+                                            }
+                                        }
+                                    });
+                                    if let Some(count) = count {
+                                        if !count.is_zero() {
+                                            session.give((output_func(key, val, val2), time.clone(), diff.clone() * count));
+                                            fuel -= 1;
+                                        }
+                                    }
+                                    cursor.step_val(&storage);
+                                }
+                                cursor.rewind_vals(&storage);
+                            }
+                        }
+                        else {
+                            pending.deferred.push(pending.updates[pending.position].clone());
+                        }
+                        pending.position += 1;
+                    }
+
+                    // once all updates are visited, only the deferred updates remain.
+                    if pending.position == pending.updates.len() {
+                        pending.updates = ::std::mem::replace(&mut pending.deferred, Vec::new());
+                        pending.position = 0;
+                    }
+                }
+
+                // Re-activate operator if work remains that need not wait for the arrangement.
+                if fuel <= 0 {
+                    activator.activate();
+                }
+            }
+
+            // drop fully processed capabilities.
+            stash.retain(|pending| !pending.updates.is_empty());
+
+            // The compaction frontier depends on both input1 and stash.
+            let mut frontier = Antichain::new();
+            for time in input1.frontier().frontier().iter() {
+                frontier.insert(time.clone());
+            }
+            for pending in stash.iter() {
+                frontier.insert(pending.capability.time().clone());
+            }
+            if let Some(ref mut trace) = trace {
+                trace.advance_by(frontier.elements());
+            }
+
+            if input1.frontier().is_empty() && stash.is_empty() {
+                trace = None;
+            }
+        }
+    })
+    .as_collection()
+}
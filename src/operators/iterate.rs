@@ -29,21 +29,64 @@
 //! and it can be used in most situations where a collection can be used. The act of setting a
 //! `Variable` consumes it and returns the corresponding `Collection`, preventing you from setting
 //! it multiple times.
+//!
+//! # Bounded iteration
+//!
+//! Some computations (approximate algorithms, or those with non-monotone logic) should not be run
+//! all the way to fixed point. The `iterate_bounded` method, and the `new_bounded` constructors on
+//! `Variable` and `SemigroupVariable`, stop feeding back updates once the iteration coordinate of
+//! the timestamp reaches a supplied limit. Rather than silently dropping the updates that would
+//! have continued the iteration, these are reported as a "residual" collection, which accumulates
+//! to empty exactly when the iteration reached a fixed point within the allowed rounds.
+//!
+//! # Instrumentation
+//!
+//! A variable that never converges simply spins. The `instrument` methods on `Variable` and
+//! `SemigroupVariable` log the number of updates fed back in each round, and report records whose
+//! updates in one round cancel their updates in the previous round, which is the typical sign of
+//! non-monotone logic oscillating rather than converging.
+//!
+//! # Programs
+//!
+//! Datalog-style computations often involve many mutually recursive relations, each defined by
+//! several rules. The `Program` type declares such relations by name and collects their rules, and
+//! when completed constructs an iterative scope in which each relation is the distinct records
+//! produced by its rules, entering inputs and leaving results on the caller's behalf. Programs with a
+//! relation declared more than once or never given a rule are rejected before any dataflow is
+//! constructed.
+//!
+//! Negation through recursion (for example, an `antijoin` against a relation that depends on the
+//! relation being defined) gives non-monotone iteration whose results can be surprising. The
+//! `Stratified` type accepts rules that declare which relations they use positively and which
+//! they negate, groups the relations into strata, and evaluates each stratum in its own iterative
+//! scope once the strata it depends on are complete. Programs with negation through recursion are
+//! rejected before any dataflow is constructed.
+//!
+//! # Semi-naive evaluation
+//!
+//! The `SemiNaive` trait mirrors classic semi-naive Datalog evaluation for a recursively defined
+//! keyed relation: rule bodies receive only the records newly added in the prior round, and the
+//! accumulated relation arranged once by key, against which the new records can be joined.
 
 use std::fmt::Debug;
 use std::ops::Deref;
+use std::collections::HashMap;
 
 use timely::progress::{Timestamp, PathSummary};
 use timely::order::Product;
 
 use timely::dataflow::*;
+use timely::dataflow::scopes::ScopeParent;
 use timely::dataflow::scopes::child::Iterative;
-use timely::dataflow::operators::{Feedback, ConnectLoop, Map};
+use timely::dataflow::operators::{Feedback, ConnectLoop, Map, Filter};
 use timely::dataflow::operators::feedback::Handle;
 
-use ::{Data, Collection};
+use ::{Data, ExchangeData, Collection, AsCollection};
 use ::difference::{Semigroup, Abelian};
 use lattice::Lattice;
+use hashable::Hashable;
+use operators::arrange::{Arranged, ArrangeByKey, TraceAgent};
+use trace::implementations::ord::OrdValSpine;
 
 /// An extension trait for the `iterate` method.
 pub trait Iterate<G: Scope, D: Data, R: Semigroup> {
@@ -80,6 +123,46 @@ pub trait Iterate<G: Scope, D: Data, R: Semigroup> {
         where
             G::Timestamp: Lattice,
             for<'a> F: FnOnce(&Collection<Iterative<'a, G, u64>, D, R>)->Collection<Iterative<'a, G, u64>, D, R>;
+
+    /// Iteratively apply `logic` to the source collection for at most `max_rounds` rounds.
+    ///
+    /// This method behaves as `iterate`, except that updates are only fed back into the loop while
+    /// their round is less than `max_rounds`. The first returned collection is the result of the
+    /// last round performed. The second returned collection contains the updates that were not fed
+    /// back because of the bound; it accumulates to empty if and only if the iteration reached a
+    /// fixed point within `max_rounds` rounds.
+    ///
+    /// The `logic` is always applied at least once, even if `max_rounds` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate timely;
+    /// extern crate differential_dataflow;
+    ///
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::Iterate;
+    /// use differential_dataflow::operators::Consolidate;
+    ///
+    /// fn main() {
+    ///     ::timely::example(|scope| {
+    ///
+    ///         let (_result, residual) =
+    ///         scope.new_collection_from(1 .. 10u32).1
+    ///              .iterate_bounded(10, |values| {
+    ///                  values.map(|x| if x % 2 == 0 { x/2 } else { x })
+    ///                        .consolidate()
+    ///              });
+    ///
+    ///         // ten rounds are enough to reach a fixed point.
+    ///         residual.assert_empty();
+    ///     });
+    /// }
+    /// ```
+    fn iterate_bounded<F>(&self, max_rounds: u64, logic: F) -> (Collection<G, D, R>, Collection<G, D, R>)
+        where
+            G::Timestamp: Lattice,
+            for<'a> F: FnOnce(&Collection<Iterative<'a, G, u64>, D, R>)->Collection<Iterative<'a, G, u64>, D, R>;
 }
 
 impl<G: Scope, D: Ord+Data+Debug, R: Abelian> Iterate<G, D, R> for Collection<G, D, R> {
@@ -100,6 +183,19 @@ impl<G: Scope, D: Ord+Data+Debug, R: Abelian> Iterate<G, D, R> for Collection<G,
             result.leave()
         })
     }
+
+    fn iterate_bounded<F>(&self, max_rounds: u64, logic: F) -> (Collection<G, D, R>, Collection<G, D, R>)
+        where G::Timestamp: Lattice,
+              for<'a> F: FnOnce(&Collection<Iterative<'a, G, u64>, D, R>)->Collection<Iterative<'a, G, u64>, D, R> {
+
+        self.inner.scope().scoped("IterateBounded", |subgraph| {
+            let variable = Variable::new_bounded_from(self.enter(subgraph), Product::new(Default::default(), 1), max_rounds);
+            let result = logic(&variable);
+            // as in `iterate`, return the output of `logic` rather than the variable.
+            let (_, residual) = variable.set_with_residual(&result);
+            (result.leave(), residual.leave())
+        })
+    }
 }
 
 impl<G: Scope, D: Ord+Data+Debug, R: Semigroup> Iterate<G, D, R> for G {
@@ -124,6 +220,268 @@ impl<G: Scope, D: Ord+Data+Debug, R: Semigroup> Iterate<G, D, R> for G {
             }
         )
     }
+
+    fn iterate_bounded<F>(&self, max_rounds: u64, logic: F) -> (Collection<G, D, R>, Collection<G, D, R>)
+        where G::Timestamp: Lattice,
+              for<'a> F: FnOnce(&Collection<Iterative<'a, G, u64>, D, R>)->Collection<Iterative<'a, G, u64>, D, R> {
+
+        let mut clone = self.clone();
+        clone
+            .scoped("IterateBounded", |subgraph| {
+                let variable = SemigroupVariable::new_bounded(subgraph, Product::new(Default::default(), 1), max_rounds);
+                let result = logic(&variable);
+                // as in `iterate`, return the output of `logic` rather than the variable.
+                let (_, residual) = variable.set_with_residual(&result);
+                (result.leave(), residual.leave())
+            }
+        )
+    }
+}
+
+/// A predicate indicating whether an update at a time should be fed back into the loop.
+type Bound<T> = Box<dyn Fn(&T)->bool>;
+
+/// Deferred logic to attach to the stream of updates fed back into the loop.
+type Instrument<S> = Box<dyn FnOnce(&S)>;
+
+/// Observes updates fed back into a variable, logging round sizes and oscillating records.
+///
+/// Updates are accumulated until their round is complete, at which point the number of distinct
+/// updated records is logged. The consolidated updates of the most recent round are retained for
+/// each outer time, and records whose updates cancel those of the immediately prior round are
+/// reported as oscillating.
+fn instrument_updates<G, T, D, R>(updates: &Stream<G, (D, Product<T, u64>, R)>, name: String, sample: usize)
+where
+    G: Scope<Timestamp=Product<T, u64>>,
+    T: Timestamp+Lattice,
+    D: Data,
+    R: Semigroup,
+{
+    use timely::order::PartialOrder;
+    use timely::dataflow::channels::pact::Pipeline;
+    use timely::dataflow::operators::generic::operator::Operator;
+    use logging::{DifferentialEvent, IterationEvent, OscillationEvent};
+
+    let logger = {
+        let scope = updates.scope();
+        let register = scope.log_register();
+        register.get::<DifferentialEvent>("differential/iterate")
+    };
+
+    // Updates for rounds that are not yet complete.
+    let mut pending: Vec<(Product<T, u64>, Vec<(D, R)>)> = Vec::new();
+    // The most recently completed round, and its consolidated updates, for each outer time.
+    let mut previous: Vec<(T, u64, Vec<(D, R)>)> = Vec::new();
+    let mut buffer = Vec::new();
+
+    updates.sink(Pipeline, "InstrumentVariable", move |input| {
+
+        input.for_each(|_time, data| {
+            data.swap(&mut buffer);
+            for (datum, time, diff) in buffer.drain(..) {
+                if let Some(position) = pending.iter().position(|x| x.0 == time) {
+                    pending[position].1.push((datum, diff));
+                }
+                else {
+                    pending.push((time, vec![(datum, diff)]));
+                }
+            }
+        });
+
+        // Extract completed rounds, and process them in order of round.
+        let mut complete = Vec::new();
+        let mut index = 0;
+        while index < pending.len() {
+            if !input.frontier().less_equal(&pending[index].0) {
+                complete.push(pending.swap_remove(index));
+            }
+            else {
+                index += 1;
+            }
+        }
+        complete.sort_by(|x,y| x.0.inner.cmp(&y.0.inner));
+
+        for (time, mut updates) in complete {
+
+            ::consolidation::consolidate(&mut updates);
+
+            if let Some(logger) = &logger {
+                logger.log(IterationEvent {
+                    name: name.clone(),
+                    round: time.inner,
+                    updates: updates.len(),
+                });
+            }
+
+            let position = previous.iter().position(|x| x.0 == time.outer);
+            if let Some(position) = position {
+
+                if previous[position].1 + 1 == time.inner {
+
+                    let prior = &previous[position].2;
+                    let oscillating =
+                    updates
+                        .iter()
+                        .filter(|&(datum, diff)| {
+                            prior
+                                .binary_search_by(|x| x.0.cmp(datum))
+                                .map(|index| { let mut sum = prior[index].1.clone(); sum += diff; sum.is_zero() })
+                                .unwrap_or(false)
+                        })
+                        .map(|(datum, _)| datum)
+                        .collect::<Vec<_>>();
+
+                    if !oscillating.is_empty() {
+                        let examples = oscillating.iter().take(sample).map(|x| format!("{:?}", x)).collect::<Vec<_>>();
+                        if let Some(logger) = &logger {
+                            logger.log(OscillationEvent {
+                                name: name.clone(),
+                                round: time.inner,
+                                oscillating: oscillating.len(),
+                                sample: examples,
+                            });
+                        }
+                    }
+                }
+
+                previous[position] = (time.outer, time.inner, updates);
+            }
+            else {
+                previous.push((time.outer, time.inner, updates));
+            }
+        }
+
+        // Discard rounds for outer times that can no longer receive updates.
+        let frontier = input.frontier().frontier();
+        previous.retain(|x| frontier.iter().any(|t| t.outer.less_equal(&x.0)));
+    });
+}
+
+/// Routes updates to either the feedback edge or the residual collection, according to `bound`.
+///
+/// Updates at times accepted by `bound` are connected to `feedback`, and the remaining updates are
+/// returned as a collection. In the absence of a bound all updates are fed back, and the returned
+/// collection is an empty stream, so that bounded and unbounded variables share `set_with_residual`.
+fn connect_bounded<G: Scope, D: Data, R: Semigroup>(
+    updates: &Stream<G, (D, G::Timestamp, R)>,
+    feedback: Handle<G, (D, G::Timestamp, R)>,
+    bound: Option<Bound<G::Timestamp>>,
+) -> Collection<G, D, R> {
+    if let Some(bound) = bound {
+        let bound = ::std::rc::Rc::new(bound);
+        let bound_clone = bound.clone();
+        updates
+            .filter(move |&(_, ref t, _)| bound_clone(t))
+            .connect_loop(feedback);
+        updates
+            .filter(move |&(_, ref t, _)| !bound(t))
+            .as_collection()
+    }
+    else {
+        updates.connect_loop(feedback);
+        ::timely::dataflow::operators::generic::operator::empty(&updates.scope()).as_collection()
+    }
+}
+
+/// The accumulated relation supplied to semi-naive rule bodies, arranged by key.
+pub type SemiNaiveTotal<G, K, V> = Arranged<G, TraceAgent<OrdValSpine<K, V, <G as ScopeParent>::Timestamp, isize>>>;
+
+/// An extension trait for the `semi_naive` method.
+pub trait SemiNaive<G: Scope, K: ExchangeData+Hashable, V: ExchangeData> where G::Timestamp: Lattice+Ord {
+    /// Computes the least set containing the source records and closed under `logic`, semi-naively.
+    ///
+    /// In each round `logic` is supplied with the records added to the relation in the prior round,
+    /// the "delta", and with the accumulated relation arranged by key. It should produce the records
+    /// derived from rules in which at least one atom is drawn from the delta and the remaining atoms
+    /// are drawn from the accumulated relation; for a rule joining the relation with itself, this is
+    /// the delta joined with the accumulated relation. The accumulated relation is arranged once per
+    /// iteration and may be shared by all rules, rather than each rule arranging its own copy.
+    ///
+    /// Records produced by `logic` are made distinct, and those already in the relation discarded, before
+    /// they are added to the relation. The accumulated relation is not itself made distinct again in each
+    /// round; only the source records are, once, before the iteration.
+    ///
+    /// # Examples
+    ///
+    /// Rules that join the relation with itself use both the delta and the accumulated relation. Here the
+    /// symmetric closure of a relation is formed from pairs of records sharing a key, at least one of which
+    /// is new, and each derived pair is produced in both orders.
+    ///
+    /// ```
+    /// extern crate timely;
+    /// extern crate differential_dataflow;
+    ///
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::{Iterate, Threshold, Join, JoinCore};
+    /// use differential_dataflow::operators::arrange::ArrangeByKey;
+    /// use differential_dataflow::operators::iterate::SemiNaive;
+    ///
+    /// fn main() {
+    ///     ::timely::example(|scope| {
+    ///
+    ///         let edges = scope.new_collection_from(vec![(0u32, 1u32), (1, 2), (3, 4)]).1;
+    ///         let symmetric = edges.map(|(x, y)| (y, x)).concat(&edges);
+    ///
+    ///         // same(x, z) :- same(y, x), same(y, z).
+    ///         let semi_naive =
+    ///         symmetric.semi_naive(|delta, total| {
+    ///             delta.arrange_by_key()
+    ///                  .join_core(total, |_y, &x, &z| vec![(x, z), (z, x)])
+    ///         });
+    ///
+    ///         let naive =
+    ///         symmetric.iterate(|same| {
+    ///             same.join_map(same, |_y, &x, &z| (x, z))
+    ///                 .concat(same)
+    ///                 .distinct()
+    ///         });
+    ///
+    ///         semi_naive.assert_eq(&naive);
+    ///     });
+    /// }
+    /// ```
+    fn semi_naive<F>(&self, logic: F) -> Collection<G, (K, V)>
+    where
+        for<'a> F: FnOnce(&Collection<Iterative<'a, G, u64>, (K, V)>, &SemiNaiveTotal<Iterative<'a, G, u64>, K, V>)->Collection<Iterative<'a, G, u64>, (K, V)>;
+}
+
+impl<G: Scope, K: ExchangeData+Hashable, V: ExchangeData> SemiNaive<G, K, V> for Collection<G, (K, V)>
+where
+    G::Timestamp: Lattice+Ord,
+    (K, V): Hashable,
+{
+    fn semi_naive<F>(&self, logic: F) -> Collection<G, (K, V)>
+    where
+        for<'a> F: FnOnce(&Collection<Iterative<'a, G, u64>, (K, V)>, &SemiNaiveTotal<Iterative<'a, G, u64>, K, V>)->Collection<Iterative<'a, G, u64>, (K, V)> {
+
+        use operators::{Threshold, Join};
+
+        self.inner.scope().scoped("SemiNaive", |subgraph| {
+
+            // The accumulated relation, and its arrangement shared by all rules.
+            let variable = Variable::new_from(self.distinct().enter(subgraph), Product::new(Default::default(), 1));
+            let total = variable.arrange_by_key();
+
+            // Records added in each round are retracted in the following round.
+            let delta =
+            variable
+                .negate()
+                .delay(|time| Product::new(time.outer.clone(), time.inner + 1))
+                .concat(&variable);
+
+            // Only derived records not already in the relation are added to it.
+            let derived = logic(&delta, &total).distinct();
+            let known =
+            derived
+                .map(|record| (record, ()))
+                .semijoin(&variable)
+                .map(|(record, ())| record);
+
+            let result = variable.concat(&derived).concat(&known.negate());
+            variable.set(&result);
+            result.leave()
+        })
+    }
 }
 
 /// A recursively defined collection.
@@ -169,6 +527,8 @@ where G::Timestamp: Lattice {
     feedback: Handle<G, (D, G::Timestamp, R)>,
     source: Collection<G, D, R>,
     step: <G::Timestamp as Timestamp>::Summary,
+    bound: Option<Bound<G::Timestamp>>,
+    instrument: Option<Instrument<Stream<G, (D, G::Timestamp, R)>>>,
 }
 
 impl<G: Scope, D: Data, R: Abelian> Variable<G, D, R> where G::Timestamp: Lattice {
@@ -183,20 +543,75 @@ impl<G: Scope, D: Data, R: Abelian> Variable<G, D, R> where G::Timestamp: Lattic
     pub fn new_from(source: Collection<G, D, R>, step: <G::Timestamp as Timestamp>::Summary) -> Self {
         let (feedback, updates) = source.inner.scope().feedback(step.clone());
         let collection = Collection::new(updates).concat(&source);
-        Variable { collection, feedback, source, step }
+        Variable { collection, feedback, source, step, bound: None, instrument: None }
     }
 
     /// Adds a new source of data to the `Variable`.
     pub fn set(self, result: &Collection<G, D, R>) -> Collection<G, D, R> {
+        self.set_with_residual(result).0
+    }
+
+    /// Adds a new source of data to the `Variable`, and reports updates not fed back.
+    ///
+    /// The second returned collection contains those updates that were not fed back into the
+    /// variable because of its bound, at the times they would have been fed back. For a variable
+    /// without a bound this collection is empty.
+    pub fn set_with_residual(self, result: &Collection<G, D, R>) -> (Collection<G, D, R>, Collection<G, D, R>) {
         let step = self.step;
+        let updates =
         self.source
             .negate()
             .concat(result)
             .inner
-            .flat_map(move |(x,t,d)| step.results_in(&t).map(|t| (x,t,d)))
-            .connect_loop(self.feedback);
+            .flat_map(move |(x,t,d)| step.results_in(&t).map(|t| (x,t,d)));
 
-        self.collection
+        if let Some(instrument) = self.instrument {
+            instrument(&updates);
+        }
+
+        let residual = connect_bounded(&updates, self.feedback, self.bound);
+        (self.collection, residual)
+    }
+}
+
+impl<G, T, D, R> Variable<G, D, R>
+where
+    G: Scope<Timestamp=Product<T, u64>>,
+    T: Timestamp+Lattice,
+    D: Data,
+    R: Abelian,
+{
+    /// Creates a new initially empty `Variable` whose rounds are less than `max_rounds`.
+    ///
+    /// Updates are only fed back into the variable if their round, the iteration coordinate of
+    /// their timestamp after applying `step`, is less than `max_rounds`. The remaining updates are
+    /// reported by `set_with_residual`.
+    pub fn new_bounded(scope: &mut G, step: <G::Timestamp as Timestamp>::Summary, max_rounds: u64) -> Self {
+        let mut result = Self::new(scope, step);
+        result.bound = Some(Box::new(move |time: &Product<T, u64>| time.inner < max_rounds));
+        result
+    }
+
+    /// Creates a new `Variable` from a supplied `source` stream, whose rounds are less than `max_rounds`.
+    pub fn new_bounded_from(source: Collection<G, D, R>, step: <G::Timestamp as Timestamp>::Summary, max_rounds: u64) -> Self {
+        let mut result = Self::new_from(source, step);
+        result.bound = Some(Box::new(move |time: &Product<T, u64>| time.inner < max_rounds));
+        result
+    }
+
+    /// Instruments the variable to report the updates fed back in each round.
+    ///
+    /// Each completed round logs an `IterationEvent` to the "differential/iterate" logger with the
+    /// number of distinct records updated. Records whose update cancels their update in the prior
+    /// round (for the same outer time) are logged as an `OscillationEvent` together with up to
+    /// `sample` formatted examples. The logger is separate from "differential/arrange", so that
+    /// iterations can be observed without the volume of batch and merge events.
+    pub fn instrument(mut self, name: &str, sample: usize) -> Self {
+        let name = name.to_owned();
+        self.instrument = Some(Box::new(move |updates: &Stream<G, (D, Product<T, u64>, R)>| {
+            instrument_updates(updates, name, sample)
+        }));
+        self
     }
 }
 
@@ -249,6 +664,8 @@ where G::Timestamp: Lattice {
     collection: Collection<G, D, R>,
     feedback: Handle<G, (D, G::Timestamp, R)>,
     step: <G::Timestamp as Timestamp>::Summary,
+    bound: Option<Bound<G::Timestamp>>,
+    instrument: Option<Instrument<Stream<G, (D, G::Timestamp, R)>>>,
 }
 
 impl<G: Scope, D: Data, R: Semigroup> SemigroupVariable<G, D, R> where G::Timestamp: Lattice {
@@ -256,18 +673,66 @@ impl<G: Scope, D: Data, R: Semigroup> SemigroupVariable<G, D, R> where G::Timest
     pub fn new(scope: &mut G, step: <G::Timestamp as Timestamp>::Summary) -> Self {
         let (feedback, updates) = scope.feedback(step.clone());
         let collection = Collection::new(updates);
-        SemigroupVariable { collection, feedback, step }
+        SemigroupVariable { collection, feedback, step, bound: None, instrument: None }
     }
 
     /// Adds a new source of data to the `Variable`.
     pub fn set(self, result: &Collection<G, D, R>) -> Collection<G, D, R> {
+        self.set_with_residual(result).0
+    }
+
+    /// Adds a new source of data to the `Variable`, and reports updates not fed back.
+    ///
+    /// The second returned collection contains those updates that were not fed back into the
+    /// variable because of its bound, at the times they would have been fed back. For a variable
+    /// without a bound this collection is empty.
+    pub fn set_with_residual(self, result: &Collection<G, D, R>) -> (Collection<G, D, R>, Collection<G, D, R>) {
         let step = self.step;
+        let updates =
         result
             .inner
-            .flat_map(move |(x,t,d)| step.results_in(&t).map(|t| (x,t,d)))
-            .connect_loop(self.feedback);
+            .flat_map(move |(x,t,d)| step.results_in(&t).map(|t| (x,t,d)));
+
+        if let Some(instrument) = self.instrument {
+            instrument(&updates);
+        }
+
+        let residual = connect_bounded(&updates, self.feedback, self.bound);
+        (self.collection, residual)
+    }
+}
 
-        self.collection
+impl<G, T, D, R> SemigroupVariable<G, D, R>
+where
+    G: Scope<Timestamp=Product<T, u64>>,
+    T: Timestamp+Lattice,
+    D: Data,
+    R: Semigroup,
+{
+    /// Creates a new initially empty `SemigroupVariable` whose rounds are less than `max_rounds`.
+    ///
+    /// Updates are only fed back into the variable if their round, the iteration coordinate of
+    /// their timestamp after applying `step`, is less than `max_rounds`. The remaining updates are
+    /// reported by `set_with_residual`.
+    pub fn new_bounded(scope: &mut G, step: <G::Timestamp as Timestamp>::Summary, max_rounds: u64) -> Self {
+        let mut result = Self::new(scope, step);
+        result.bound = Some(Box::new(move |time: &Product<T, u64>| time.inner < max_rounds));
+        result
+    }
+
+    /// Instruments the variable to report the updates fed back in each round.
+    ///
+    /// Each completed round logs an `IterationEvent` to the "differential/iterate" logger with the
+    /// number of distinct records updated. Records whose update cancels their update in the prior
+    /// round (for the same outer time) are logged as an `OscillationEvent` together with up to
+    /// `sample` formatted examples. The logger is separate from "differential/arrange", so that
+    /// iterations can be observed without the volume of batch and merge events.
+    pub fn instrument(mut self, name: &str, sample: usize) -> Self {
+        let name = name.to_owned();
+        self.instrument = Some(Box::new(move |updates: &Stream<G, (D, Product<T, u64>, R)>| {
+            instrument_updates(updates, name, sample)
+        }));
+        self
     }
 }
 
@@ -276,4 +741,466 @@ impl<G: Scope, D: Data, R: Semigroup> Deref for SemigroupVariable<G, D, R> where
     fn deref(&self) -> &Self::Target {
         &self.collection
     }
-}
\ No newline at end of file
+}
+
+/// A set of mutually recursive relations, each defined by rules.
+///
+/// Relations are declared by name with `relation` or `relation_from`, and named collections that the
+/// rules may use but not define are added with `input`. Rules for a relation are added with `rule`,
+/// whose logic is provided with a `Body` through which it can use any relation or input, including the
+/// relation it defines, and bring other collections into the iteration with `enter`. All relations share
+/// the record type `D`, which is commonly an enumeration or a vector of values.
+///
+/// Once all rules are added, `complete` checks that each relation is declared once and given rules,
+/// and only then constructs an iterative scope in which each relation is the distinct records produced
+/// by its rules (and its initial contents). The result maps the name of each relation to its contents
+/// outside the iterative scope, so that no relation can be left unconnected.
+///
+/// # Examples
+///
+/// ```
+/// extern crate timely;
+/// extern crate differential_dataflow;
+///
+/// use differential_dataflow::input::Input;
+/// use differential_dataflow::operators::Join;
+/// use differential_dataflow::operators::iterate::Program;
+///
+/// fn main() {
+///     ::timely::example(|scope| {
+///
+///         let edges = scope.new_collection_from(vec![(0u32, 1u32), (1, 2), (2, 3)]).1;
+///
+///         let mut program = Program::new();
+///         program.input("edge", &edges);
+///         program.relation("reach");
+///         // reach(x, y) :- edge(x, y).
+///         program.rule("reach", |body| body.get("edge").clone());
+///         // reach(x, z) :- reach(x, y), edge(y, z), with edges entered directly.
+///         program.rule("reach", move |body| {
+///             body.get("reach")
+///                 .map(|(x, y)| (y, x))
+///                 .join_map(&body.enter(&edges), |_y, &x, &z| (x, z))
+///         });
+///
+///         let results = program.complete(scope).expect("malformed program");
+///         let expected = vec![(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)];
+///         results["reach"].assert_eq(&scope.new_collection_from(expected).1);
+///     });
+/// }
+/// ```
+pub struct Program<'r, G: Scope, D: Data> where G::Timestamp: Lattice+Ord {
+    inputs: Vec<(String, Collection<G, D>)>,
+    relations: Vec<(String, Option<Collection<G, D>>)>,
+    rules: Vec<(String, RuleLogic<'r, G, D>)>,
+}
+
+impl<'r, G: Scope, D: ExchangeData+Hashable> Program<'r, G, D> where G::Timestamp: Lattice+Ord {
+
+    /// Creates a new program without relations, inputs, or rules.
+    pub fn new() -> Self {
+        Program {
+            inputs: Vec::new(),
+            relations: Vec::new(),
+            rules: Vec::new(),
+        }
+    }
+
+    /// Adds a named input, which rules may use but not define.
+    pub fn input(&mut self, name: &str, collection: &Collection<G, D>) -> &mut Self {
+        self.inputs.push((name.to_owned(), collection.clone()));
+        self
+    }
+
+    /// Declares a new initially empty relation named `name`.
+    pub fn relation(&mut self, name: &str) -> &mut Self {
+        self.relations.push((name.to_owned(), None));
+        self
+    }
+
+    /// Declares a new relation named `name`, whose initial contents are `source`.
+    ///
+    /// The records of `source` remain in the relation in every round, in addition to those produced
+    /// by its rules.
+    pub fn relation_from(&mut self, name: &str, source: &Collection<G, D>) -> &mut Self {
+        self.relations.push((name.to_owned(), Some(source.clone())));
+        self
+    }
+
+    /// Adds the records produced by `logic` to the definition of the relation `head`.
+    pub fn rule<L>(&mut self, head: &str, logic: L) -> &mut Self
+    where L: for<'a> Fn(&Body<'a, G, D>)->Collection<Iterative<'a, G, u64>, D>+'r {
+        self.rules.push((head.to_owned(), Box::new(logic)));
+        self
+    }
+
+    /// Constructs the iterative scope for the program in `scope`, and returns the contents of each relation.
+    ///
+    /// The result is an error describing each name declared more than once, each relation without
+    /// rules, and each rule for a name that is not a declared relation. No dataflow is constructed for
+    /// rejected programs.
+    pub fn complete(self, scope: &mut G) -> Result<HashMap<String, Collection<G, D>>, String> {
+
+        use operators::Threshold;
+
+        let mut errors = Vec::new();
+        let names = self.inputs.iter().map(|x| &x.0).chain(self.relations.iter().map(|x| &x.0)).collect::<Vec<_>>();
+        for (index, name) in names.iter().enumerate() {
+            if names[.. index].contains(name) && !names[index + 1 ..].contains(name) {
+                errors.push(format!("relation {:?} declared more than once", name));
+            }
+        }
+        for &(ref name, _) in self.relations.iter() {
+            if !self.rules.iter().any(|x| &x.0 == name) {
+                errors.push(format!("relation {:?} has no rules", name));
+            }
+        }
+        for &(ref head, _) in self.rules.iter() {
+            if self.inputs.iter().any(|x| &x.0 == head) {
+                errors.push(format!("input relation {:?} cannot also be defined by rules", head));
+            }
+            else if !self.relations.iter().any(|x| &x.0 == head) {
+                errors.push(format!("relation {:?} has rules but was not declared", head));
+            }
+        }
+        if !errors.is_empty() {
+            errors.dedup();
+            return Err(errors.join("; "));
+        }
+
+        let (inputs, relations, rules) = (self.inputs, self.relations, self.rules);
+        Ok(scope.iterative::<u64,_,_>(|nested| {
+
+
+            let variables = relations.into_iter().map(|(name, source)| {
+                let source = match source {
+                    Some(source) => source.enter(nested),
+                    None => ::timely::dataflow::operators::generic::operator::empty(nested).as_collection(),
+                };
+                let variable = Variable::new_from(source.clone(), Product::new(Default::default(), 1));
+                (name, variable, source)
+            }).collect::<Vec<_>>();
+
+            let body = Body {
+                scope: nested.clone(),
+                relations:
+                    inputs.iter().map(|&(ref name, ref collection)| (name.clone(), collection.enter(nested)))
+                        .chain(variables.iter().map(|&(ref name, ref variable, _)| (name.clone(), (**variable).clone())))
+                        .collect(),
+            };
+
+            let mut produced = rules.iter().map(|&(ref head, ref logic)| (head.clone(), logic(&body))).collect::<Vec<_>>();
+
+            variables.into_iter().map(|(name, variable, source)| {
+                let definition = produced.iter().filter(|x| x.0 == name).map(|x| x.1.clone()).collect::<Vec<_>>();
+                produced.retain(|x| x.0 != name);
+                let contents = variable.set(&source.concatenate(definition).distinct());
+                (name, contents.leave())
+            }).collect()
+        }))
+    }
+}
+
+impl<'r, G: Scope, D: ExchangeData+Hashable> Default for Program<'r, G, D> where G::Timestamp: Lattice+Ord {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The logic of a rule in a program, which may borrow or capture collections for the lifetime `'r`.
+type RuleLogic<'r, G, D> = Box<dyn for<'a> Fn(&Body<'a, G, D>)->Collection<Iterative<'a, G, u64>, D>+'r>;
+
+/// A rule in a stratified program.
+struct StratifiedRule<G: Scope, D: Data> where G::Timestamp: Lattice+Ord {
+    head: String,
+    positive: Vec<String>,
+    negative: Vec<String>,
+    logic: RuleLogic<'static, G, D>,
+}
+
+/// Recursive rules with negation, evaluated one stratum at a time.
+///
+/// Each rule names the relation it defines, the relations it uses positively, and the relations
+/// it negates, and provides logic that produces records from these relations. All relations share
+/// the record type `D`, which is commonly an enumeration or a vector of values.
+///
+/// The `build` method groups relations into strata, the strongly connected components of the
+/// dependences between relations, and rejects any program in which a relation is negated within its
+/// own stratum. Each stratum is then evaluated as a `Program` in its own iterative scope, with the
+/// results of earlier strata as its inputs, so that negation only ever applies to completed relations.
+///
+/// # Examples
+///
+/// ```
+/// extern crate timely;
+/// extern crate differential_dataflow;
+///
+/// use differential_dataflow::input::Input;
+/// use differential_dataflow::operators::Join;
+/// use differential_dataflow::operators::iterate::Stratified;
+///
+/// fn main() {
+///     ::timely::example(|scope| {
+///
+///         let edges = scope.new_collection_from(vec![(0u32, 1u32), (1, 2), (3, 4)]).1;
+///         let nodes = scope.new_collection_from(vec![(0u32, 0u32), (1, 1), (2, 2), (3, 3), (4, 4)]).1;
+///
+///         let mut program = Stratified::new();
+///         program.input("edge", &edges);
+///         program.input("node", &nodes);
+///         // reach(x, y) :- edge(x, y).
+///         program.rule("reach", &["edge"], &[], |body| body.get("edge").clone());
+///         // reach(x, z) :- reach(x, y), edge(y, z).
+///         program.rule("reach", &["reach", "edge"], &[], |body| {
+///             body.get("reach")
+///                 .map(|(x, y)| (y, x))
+///                 .join_map(body.get("edge"), |_y, &x, &z| (x, z))
+///         });
+///         // unreached(x, y) :- node(x, x), node(y, y), !reach(x, y).
+///         program.rule("unreached", &["node"], &["reach"], |body| {
+///             let nodes = body.get("node").map(|(x, _)| x);
+///             let pairs = nodes.map(|x| ((), x)).join_map(&nodes.map(|y| ((), y)), |_, &x, &y| (x, y));
+///             pairs.concat(&body.get("reach").negate())
+///         });
+///
+///         let results = program.build(scope).expect("program should be stratifiable");
+///         let expected = scope.new_collection_from(vec![(0u32, 3u32), (0, 4)]).1;
+///         results["unreached"].filter(|&(x, y)| x == 0 && x != y).assert_eq(&expected);
+///     });
+/// }
+/// ```
+pub struct Stratified<G: Scope, D: Data> where G::Timestamp: Lattice+Ord {
+    inputs: Vec<(String, Collection<G, D>)>,
+    rules: Vec<StratifiedRule<G, D>>,
+}
+
+impl<G: Scope, D: ExchangeData+Hashable> Stratified<G, D> where G::Timestamp: Lattice+Ord {
+
+    /// Creates a new program without inputs or rules.
+    pub fn new() -> Self {
+        Stratified {
+            inputs: Vec::new(),
+            rules: Vec::new(),
+        }
+    }
+
+    /// Adds a named input relation, whose contents are not defined by rules.
+    pub fn input(&mut self, name: &str, collection: &Collection<G, D>) -> &mut Self {
+        self.inputs.push((name.to_owned(), collection.clone()));
+        self
+    }
+
+    /// Adds a rule defining records of `head`.
+    ///
+    /// The rule may only use the relations named in `positive` and `negative`, through the `Body`
+    /// supplied to `logic`. Relations in `negative` must be completely defined in earlier strata.
+    pub fn rule<L>(&mut self, head: &str, positive: &[&str], negative: &[&str], logic: L) -> &mut Self
+    where L: for<'a> Fn(&Body<'a, G, D>)->Collection<Iterative<'a, G, u64>, D>+'static {
+        self.rules.push(StratifiedRule {
+            head: head.to_owned(),
+            positive: positive.iter().map(|x| x.to_string()).collect(),
+            negative: negative.iter().map(|x| x.to_string()).collect(),
+            logic: Box::new(logic),
+        });
+        self
+    }
+
+    /// Groups the defined relations into strata, in order of evaluation.
+    ///
+    /// The result is an error if a rule uses an undefined relation, if an input also has rules,
+    /// or if a relation is negated by a rule defining a relation in the same stratum.
+    pub fn strata(&self) -> Result<Vec<Vec<String>>, String> {
+
+        // Each defined relation, in order of first definition.
+        let mut heads = Vec::<&str>::new();
+        for rule in self.rules.iter() {
+            if self.inputs.iter().any(|x| x.0 == rule.head) {
+                return Err(format!("input relation {:?} cannot also be defined by rules", rule.head));
+            }
+            if !heads.contains(&&rule.head[..]) {
+                heads.push(&rule.head);
+            }
+        }
+
+        // Dependences from each defined relation to the defined relations its rules use.
+        let mut depends = vec![Vec::new(); heads.len()];
+        for rule in self.rules.iter() {
+            let head = heads.iter().position(|x| *x == rule.head).unwrap();
+            for name in rule.positive.iter().chain(rule.negative.iter()) {
+                if let Some(index) = heads.iter().position(|x| *x == *name) {
+                    depends[head].push(index);
+                }
+                else if !self.inputs.iter().any(|x| &x.0 == name) {
+                    return Err(format!("relation {:?} used by a rule for {:?} is neither an input nor defined by rules", name, rule.head));
+                }
+            }
+        }
+
+        // Strongly connected components, each after those it depends on.
+        let components = strongly_connected(&depends);
+        let mut component = vec![0; heads.len()];
+        for (index, members) in components.iter().enumerate() {
+            for &member in members.iter() {
+                component[member] = index;
+            }
+        }
+
+        for rule in self.rules.iter() {
+            let head = heads.iter().position(|x| *x == rule.head).unwrap();
+            for name in rule.negative.iter() {
+                if let Some(index) = heads.iter().position(|x| *x == *name) {
+                    if component[index] == component[head] {
+                        let members = components[component[head]].iter().map(|&x| heads[x]).collect::<Vec<_>>();
+                        return Err(format!("relation {:?} is negated by a rule for {:?} through recursion among {:?}", name, rule.head, members));
+                    }
+                }
+            }
+        }
+
+        Ok(components
+            .into_iter()
+            .map(|members| members.into_iter().map(|x| heads[x].to_owned()).collect())
+            .collect())
+    }
+
+    /// Constructs the dataflow for the program in `scope`, one stratum at a time.
+    ///
+    /// The result maps the name of each input and defined relation to its contents, or is an error
+    /// if the program cannot be stratified. No dataflow is constructed for rejected programs.
+    pub fn build(self, scope: &mut G) -> Result<HashMap<String, Collection<G, D>>, String> {
+
+        let strata = self.strata()?;
+        let mut results = self.inputs.into_iter().collect::<HashMap<_,_>>();
+        let mut rules = self.rules;
+
+        for stratum in strata {
+
+            let (current, later): (Vec<_>, Vec<_>) = rules.into_iter().partition(|x| stratum.contains(&x.head));
+            rules = later;
+
+            let mut program = Program::new();
+            for name in stratum.iter() {
+                program.relation(name);
+            }
+            for rule in current {
+                let uses = rule.positive.into_iter().chain(rule.negative.into_iter()).collect::<Vec<_>>();
+                for name in uses.iter().filter(|x| !stratum.contains(x)) {
+                    if !program.inputs.iter().any(|x| &x.0 == name) {
+                        program.input(name, &results[name]);
+                    }
+                }
+                let logic = rule.logic;
+                program.rule(&rule.head, move |body| logic(&body.restrict(&uses)));
+            }
+
+            results.extend(program.complete(scope).expect("each relation in a stratum has a rule"));
+        }
+
+        Ok(results)
+    }
+}
+
+impl<G: Scope, D: ExchangeData+Hashable> Default for Stratified<G, D> where G::Timestamp: Lattice+Ord {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The relations available to the logic of a rule, within the iterative scope of its program.
+pub struct Body<'a, G: Scope, D: Data> where G::Timestamp: Lattice+Ord {
+    scope: Iterative<'a, G, u64>,
+    relations: Vec<(String, Collection<Iterative<'a, G, u64>, D>)>,
+}
+
+impl<'a, G: Scope, D: Data> Body<'a, G, D> where G::Timestamp: Lattice+Ord {
+    /// The contents of the relation `name`.
+    ///
+    /// This method panics if `name` is not available to the rule: in a `Program` each relation and
+    /// input is available, and in a `Stratified` program only those the rule lists as used.
+    pub fn get(&self, name: &str) -> &Collection<Iterative<'a, G, u64>, D> {
+        match self.relations.iter().find(|x| x.0 == name) {
+            Some(relation) => &relation.1,
+            None => panic!("relation {:?} not available to the rule", name),
+        }
+    }
+
+    /// Brings a collection from outside the program into its iterative scope.
+    pub fn enter<D2: Data, R: Semigroup>(&self, collection: &Collection<G, D2, R>) -> Collection<Iterative<'a, G, u64>, D2, R> {
+        collection.enter(&self.scope)
+    }
+
+    /// The same body, restricted to the relations named in `names`.
+    fn restrict(&self, names: &[String]) -> Self {
+        Body {
+            scope: self.scope.clone(),
+            relations: self.relations.iter().filter(|x| names.contains(&x.0)).cloned().collect(),
+        }
+    }
+}
+
+/// Strongly connected components of a directed graph, each listed after those it can reach.
+fn strongly_connected(edges: &[Vec<usize>]) -> Vec<Vec<usize>> {
+
+    struct Tarjan<'a> {
+        edges: &'a [Vec<usize>],
+        index: Vec<Option<usize>>,
+        lowlink: Vec<usize>,
+        on_stack: Vec<bool>,
+        stack: Vec<usize>,
+        next: usize,
+        components: Vec<Vec<usize>>,
+    }
+
+    impl<'a> Tarjan<'a> {
+        fn visit(&mut self, node: usize) {
+            self.index[node] = Some(self.next);
+            self.lowlink[node] = self.next;
+            self.next += 1;
+            self.stack.push(node);
+            self.on_stack[node] = true;
+
+            for &other in self.edges[node].iter() {
+                match self.index[other] {
+                    None => {
+                        self.visit(other);
+                        self.lowlink[node] = ::std::cmp::min(self.lowlink[node], self.lowlink[other]);
+                    },
+                    Some(index) => {
+                        if self.on_stack[other] {
+                            self.lowlink[node] = ::std::cmp::min(self.lowlink[node], index);
+                        }
+                    },
+                }
+            }
+
+            if Some(self.lowlink[node]) == self.index[node] {
+                let mut component = Vec::new();
+                while let Some(other) = self.stack.pop() {
+                    self.on_stack[other] = false;
+                    component.push(other);
+                    if other == node { break; }
+                }
+                component.sort();
+                self.components.push(component);
+            }
+        }
+    }
+
+    let mut tarjan = Tarjan {
+        edges,
+        index: vec![None; edges.len()],
+        lowlink: vec![0; edges.len()],
+        on_stack: vec![false; edges.len()],
+        stack: Vec::new(),
+        next: 0,
+        components: Vec::new(),
+    };
+
+    for node in 0 .. edges.len() {
+        if tarjan.index[node].is_none() {
+            tarjan.visit(node);
+        }
+    }
+
+    tarjan.components
+}
@@ -14,7 +14,7 @@ use timely::dataflow::channels::pact::Pipeline;
 use timely::dataflow::operators::Capability;
 use timely::dataflow::channels::pushers::tee::Tee;
 
-use hashable::Hashable;
+use hashable::{Hashable, Partitioner};
 use ::{Data, ExchangeData, Collection, AsCollection};
 use ::difference::{Semigroup, Abelian};
 use lattice::Lattice;
@@ -87,6 +87,84 @@ pub trait Join<G: Scope, K: Data, V: Data, R: Semigroup> {
     fn join_map<V2, R2, D, L>(&self, other: &Collection<G, (K,V2), R2>, logic: L) -> Collection<G, D, <R as Mul<R2>>::Output>
     where K: ExchangeData, V2: ExchangeData, R2: ExchangeData+Semigroup, R: Mul<R2>, <R as Mul<R2>>::Output: Semigroup, D: Data, L: FnMut(&K, &V, &V2)->D+'static;
 
+    /// Matches pairs `(key,val1)` and `(key,val2)` based on `key`, assigning keys to workers with `partitioner`.
+    ///
+    /// Both inputs are arranged using `partitioner` rather than `hashed()`. When applied to an arrangement,
+    /// the arrangement must have been formed with the same partitioner.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate timely;
+    /// extern crate differential_dataflow;
+    ///
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::hashable::SeededPartitioner;
+    /// use differential_dataflow::operators::Join;
+    ///
+    /// fn main() {
+    ///     ::timely::example(|scope| {
+    ///
+    ///         let x = scope.new_collection_from(vec![(0, 1), (1, 3)]).1;
+    ///         let y = scope.new_collection_from(vec![(0, 'a'), (1, 'b')]).1;
+    ///         let z = scope.new_collection_from(vec![(0, (1, 'a')), (1, (3, 'b'))]).1;
+    ///
+    ///         x.join_partitioned(&y, SeededPartitioner::new(0x5eed))
+    ///          .assert_eq(&z);
+    ///     });
+    /// }
+    /// ```
+    fn join_partitioned<V2, R2, P>(&self, other: &Collection<G, (K,V2), R2>, partitioner: P) -> Collection<G, (K,(V,V2)), <R as Mul<R2>>::Output>
+    where
+        K: ExchangeData,
+        V2: ExchangeData,
+        R2: ExchangeData+Semigroup,
+        R: Mul<R2>,
+        <R as Mul<R2>>::Output: Semigroup,
+        P: Partitioner<K>+Clone,
+    {
+        self.join_map_partitioned(other, partitioner, |k,v,v2| (k.clone(),(v.clone(),v2.clone())))
+    }
+
+    /// Matches pairs `(key,val1)` and `(key,val2)` based on `key`, replicating `other` at every worker.
+    ///
+    /// The input `other` is arranged with `arrange_broadcast`, and `self` is joined with it at the workers
+    /// where its records already are, without exchanging them. This is appropriate when `other` is small
+    /// and `self` is large, for example when looking up a dimension table from a stream of facts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate timely;
+    /// extern crate differential_dataflow;
+    ///
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::Join;
+    ///
+    /// fn main() {
+    ///     ::timely::example(|scope| {
+    ///
+    ///         let x = scope.new_collection_from(vec![(0, 1), (1, 3)]).1;
+    ///         let y = scope.new_collection_from(vec![(0, 'a'), (1, 'b')]).1;
+    ///         let z = scope.new_collection_from(vec![(0, (1, 'a')), (1, (3, 'b'))]).1;
+    ///
+    ///         x.join_broadcast(&y)
+    ///          .assert_eq(&z);
+    ///     });
+    /// }
+    /// ```
+    fn join_broadcast<V2, R2>(&self, other: &Collection<G, (K,V2), R2>) -> Collection<G, (K,(V,V2)), <R as Mul<R2>>::Output>
+    where
+        K: ExchangeData,
+        V2: ExchangeData,
+        R2: ExchangeData+Semigroup,
+        R: Mul<R2>,
+        <R as Mul<R2>>::Output: Semigroup;
+
+    /// As `join_map`, assigning keys to workers with `partitioner`.
+    fn join_map_partitioned<V2, R2, D, L, P>(&self, other: &Collection<G, (K,V2), R2>, partitioner: P, logic: L) -> Collection<G, D, <R as Mul<R2>>::Output>
+    where K: ExchangeData, V2: ExchangeData, R2: ExchangeData+Semigroup, R: Mul<R2>, <R as Mul<R2>>::Output: Semigroup, D: Data, L: FnMut(&K, &V, &V2)->D+'static, P: Partitioner<K>+Clone;
+
     /// Matches pairs `(key, val)` and `key` based on `key`, producing the former with frequencies multiplied.
     ///
     /// When the second collection contains frequencies that are either zero or one this is the more traditional
@@ -166,6 +244,22 @@ where
         arranged1.join_core(&arranged2, move |k,v1,v2| Some(logic(k,v1,v2)))
     }
 
+    fn join_map_partitioned<V2: ExchangeData, R2: ExchangeData+Semigroup, D: Data, L, P>(&self, other: &Collection<G, (K, V2), R2>, partitioner: P, mut logic: L) -> Collection<G, D, <R as Mul<R2>>::Output>
+    where R: Mul<R2>, <R as Mul<R2>>::Output: Semigroup, L: FnMut(&K, &V, &V2)->D+'static, P: Partitioner<K>+Clone {
+        let arranged1 = self.arrange_by_key_partitioned(partitioner.clone());
+        let arranged2 = other.arrange_by_key_partitioned(partitioner);
+        arranged1.join_core(&arranged2, move |k,v1,v2| Some(logic(k,v1,v2)))
+    }
+
+    fn join_broadcast<V2: ExchangeData, R2: ExchangeData+Semigroup>(&self, other: &Collection<G, (K, V2), R2>) -> Collection<G, (K, (V, V2)), <R as Mul<R2>>::Output>
+    where R: Mul<R2>, <R as Mul<R2>>::Output: Semigroup {
+        use trace::implementations::ord::OrdValSpine;
+        use operators::arrange::Arrange;
+        let arranged1 = self.arrange_core::<_,OrdValSpine<K,V,G::Timestamp,R>>(Pipeline, "ArrangeLocal");
+        let arranged2 = other.arrange_broadcast();
+        arranged1.join_core(&arranged2, |k,v1,v2| Some((k.clone(),(v1.clone(),v2.clone()))))
+    }
+
     fn semijoin<R2: ExchangeData+Semigroup>(&self, other: &Collection<G, K, R2>) -> Collection<G, (K, V), <R as Mul<R2>>::Output>
     where R: Mul<R2>, <R as Mul<R2>>::Output: Semigroup {
         let arranged1 = self.arrange_by_key();
@@ -196,6 +290,18 @@ where
         self.join_core(&arranged2, move |k,v1,v2| Some(logic(k,v1,v2)))
     }
 
+    fn join_map_partitioned<V2: ExchangeData, R2: ExchangeData+Semigroup, D: Data, L, P>(&self, other: &Collection<G, (Tr::Key, V2), R2>, partitioner: P, mut logic: L) -> Collection<G, D, <Tr::R as Mul<R2>>::Output>
+    where Tr::Key: ExchangeData, Tr::R: Mul<R2>, <Tr::R as Mul<R2>>::Output: Semigroup, L: FnMut(&Tr::Key, &Tr::Val, &V2)->D+'static, P: Partitioner<Tr::Key>+Clone {
+        let arranged2 = other.arrange_by_key_partitioned(partitioner);
+        self.join_core(&arranged2, move |k,v1,v2| Some(logic(k,v1,v2)))
+    }
+
+    fn join_broadcast<V2: ExchangeData, R2: ExchangeData+Semigroup>(&self, other: &Collection<G, (Tr::Key, V2), R2>) -> Collection<G, (Tr::Key, (Tr::Val, V2)), <Tr::R as Mul<R2>>::Output>
+    where Tr::Key: ExchangeData, Tr::R: Mul<R2>, <Tr::R as Mul<R2>>::Output: Semigroup {
+        let arranged2 = other.arrange_broadcast();
+        self.join_core(&arranged2, |k,v1,v2| Some((k.clone(),(v1.clone(),v2.clone()))))
+    }
+
     fn semijoin<R2: ExchangeData+Semigroup>(&self, other: &Collection<G, Tr::Key, R2>) -> Collection<G, (Tr::Key, Tr::Val), <Tr::R as Mul<R2>>::Output>
     where Tr::Key: ExchangeData, Tr::R: Mul<R2>, <Tr::R as Mul<R2>>::Output: Semigroup {
         let arranged2 = other.arrange_by_self();
@@ -209,6 +315,123 @@ where
     }
 }
 
+/// A join that spreads the work for frequent keys across workers.
+pub trait JoinSkewed<G: Scope, K: Data, V: Data, R: Semigroup> where G::Timestamp: Lattice+Ord {
+    /// Matches pairs `(key,val1)` and `(key,val2)` based on `key`, avoiding imbalance from frequent keys.
+    ///
+    /// The frequency of each key in `self` is estimated from one in `sample` of its distinct records,
+    /// and keys estimated to have at least `threshold` records are heavy hitters. Records of `self`
+    /// with heavy keys are spread across workers by their values, and the records of `other` with those
+    /// keys are replicated to every worker. Other keys are joined as by `join`, and the result is the
+    /// same as that of `join`, whichever keys are heavy.
+    ///
+    /// The input `self` should be the larger input, as the records of `other` with heavy keys are
+    /// replicated. The records of `self` are arranged on the worker that holds them, and separated there
+    /// by whether their key is heavy, so that records with heavy keys are never exchanged by key. Keys
+    /// cross between the two strategies as their estimated frequencies change, with some cost to re-join
+    /// their records.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate timely;
+    /// extern crate differential_dataflow;
+    ///
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::operators::Join;
+    /// use differential_dataflow::operators::join::JoinSkewed;
+    ///
+    /// fn main() {
+    ///     ::timely::example(|scope| {
+    ///
+    ///         // key zero has many more records than key one.
+    ///         let x = scope.new_collection_from((0 .. 100).map(|x| (x % 10 / 9, x))).1;
+    ///         let y = scope.new_collection_from(vec![(0, 'a'), (1, 'b')]).1;
+    ///
+    ///         x.join_skewed(&y, 50, 1)
+    ///          .assert_eq(&x.join(&y));
+    ///     });
+    /// }
+    /// ```
+    fn join_skewed<V2, R2>(&self, other: &Collection<G, (K,V2), R2>, threshold: usize, sample: u64) -> Collection<G, (K,(V,V2)), <R as Mul<R2>>::Output>
+    where
+        K: ExchangeData,
+        V2: ExchangeData,
+        R2: ExchangeData+Abelian+Mul<isize, Output=R2>,
+        R: Mul<R2>,
+        <R as Mul<R2>>::Output: Semigroup;
+}
+
+impl<G, K, V, R> JoinSkewed<G, K, V, R> for Collection<G, (K, V), R>
+where
+    G: Scope,
+    G::Timestamp: Lattice+Ord,
+    K: ExchangeData+Hashable,
+    V: ExchangeData,
+    R: ExchangeData+Abelian+Mul<isize, Output=R>,
+    (K, V): Hashable,
+    (K, u64): Hashable,
+{
+    fn join_skewed<V2, R2>(&self, other: &Collection<G, (K,V2), R2>, threshold: usize, sample: u64) -> Collection<G, (K,(V,V2)), <R as Mul<R2>>::Output>
+    where
+        V2: ExchangeData,
+        R2: ExchangeData+Abelian+Mul<isize, Output=R2>,
+        R: Mul<R2>,
+        <R as Mul<R2>>::Output: Semigroup,
+    {
+        use timely::dataflow::channels::pact::Exchange;
+        use timely_sort::Unsigned;
+        use trace::implementations::ord::OrdValSpine;
+        use operators::arrange::Arrange;
+        use operators::reduce::{Count, Reduce};
+        use operators::Threshold;
+
+        let peers = self.inner.scope().peers() as u64;
+        let sample = ::std::cmp::max(sample, 1);
+
+        // Estimate the number of distinct records for each key, from a sample of those records.
+        let heavy =
+        self.filter(move |x| x.hashed().as_u64() % sample == 0)
+            .distinct()
+            .map(|(k,_)| k)
+            .count()
+            .filter(move |&(_, count)| (count as u64) * sample >= threshold as u64)
+            .map(|(k,_)| k);
+
+        // Replicate the heavy keys to every worker.
+        let heavy_keys =
+        heavy.flat_map(move |k| (0 .. peers).map(move |worker| (k.clone(), worker)))
+             .arrange_core::<_,OrdValSpine<K,u64,G::Timestamp,isize>>(Exchange::new(|x: &((K,u64),G::Timestamp,isize)| (x.0).1), "ReplicateHeavy");
+
+        // Separate the records of `self` where they are, against the local keys that are and are not heavy.
+        // The records are arranged without exchange so that those of a key can move between strategies
+        // when its class changes; only the records with light keys are then exchanged by key.
+        let local_self = self.arrange_core::<_,OrdValSpine<K,V,G::Timestamp,R>>(Pipeline, "LocalSkewed");
+        let light_keys =
+        local_self
+            .reduce_named("LocalKeys", |_k, _s, t| t.push(((), 1isize)))
+            .concat(&heavy_keys.as_collection(|k,_| (k.clone(), ())).negate())
+            .arrange_core::<_,OrdValSpine<K,(),G::Timestamp,isize>>(Pipeline, "LocalLight");
+        let heavy_self = local_self.join_core(&heavy_keys, |k,v,_| Some((k.clone(), v.clone())));
+        let light_self = local_self.join_core(&light_keys, |k,v,_| Some((k.clone(), v.clone())));
+
+        // Records of `other` are few for each heavy key, and are separated as usual.
+        let heavy_other = other.semijoin(&heavy);
+        let light_other = other.concat(&heavy_other.negate());
+
+        // Heavy keys are joined at the worker named by a salt: a hash of the value for `self`, and all
+        // workers for `other`.
+        let partitioner = |x: &(K,u64)| x.1;
+        let heavy_self = heavy_self.map(move |(k,v)| { let salt = (k.clone(), v.clone()).hashed().as_u64() % peers; ((k, salt), v) });
+        let heavy_other = heavy_other.flat_map(move |(k,v2)| (0 .. peers).map(move |salt| ((k.clone(), salt), v2.clone())));
+        let heavy_join = heavy_self.join_map_partitioned(&heavy_other, partitioner, |k,v,v2| (k.0.clone(), (v.clone(), v2.clone())));
+
+        light_self
+            .join(&light_other)
+            .concat(&heavy_join)
+    }
+}
+
 /// Matches the elements of two arranged traces.
 ///
 /// This method is used by the various `join` implementations, but it can also be used
@@ -18,6 +18,8 @@ pub mod iterate;
 pub mod join;
 pub mod count;
 pub mod threshold;
+pub mod delta;
+pub mod half_join;
 
 use ::difference::Semigroup;
 use lattice::Lattice;
@@ -192,10 +194,7 @@ where
         found
     }
     fn advance_buffer_by(&mut self, meet: &T) {
-        for element in self.replay.buffer.iter_mut() {
-            (element.0).1 = (element.0).1.join(meet);
-        }
-        crate::consolidation::consolidate(&mut self.replay.buffer);
+        ::lattice::advance_and_consolidate_data(&mut self.replay.buffer, ::std::slice::from_ref(meet));
     }
     fn is_done(&self) -> bool { self.replay.history.len() == 0 }
 
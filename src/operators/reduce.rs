@@ -7,12 +7,12 @@
 
 use hashable::Hashable;
 use ::{Data, ExchangeData, Collection};
-use ::difference::{Semigroup, Abelian};
+use ::difference::{Semigroup, Monoid, Abelian};
 
 use timely::order::PartialOrder;
 use timely::progress::frontier::Antichain;
 use timely::dataflow::*;
-use timely::dataflow::operators::Operator;
+use timely::dataflow::operators::{Operator, Inspect};
 use timely::dataflow::channels::pact::Pipeline;
 use timely::dataflow::operators::Capability;
 
@@ -132,6 +132,18 @@ pub trait Threshold<G: Scope, K: Data, R1: Semigroup> where G::Timestamp: Lattic
     /// A `threshold` with the ability to name the operator.
     fn threshold_named<R2: Abelian, F: FnMut(&K, &R1)->R2+'static>(&self, name: &str, thresh: F) -> Collection<G, K, R2>;
 
+    /// Transforms the multiplicity of records, for output differences without negation.
+    ///
+    /// The transformed multiplicities may only change in ways that `ReduceCore::reduce_semigroup`
+    /// supports: a record may not disappear, and its new difference must be reachable by addition to
+    /// its prior difference. This is the case for monotone transformations of append-only inputs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an input multiplicity is negative, or if the transformed multiplicity of a record
+    /// would require a retraction.
+    fn threshold_semigroup<R2: Semigroup, F: FnMut(&K, &R1)->R2+'static>(&self, name: &str, thresh: F) -> Collection<G, K, R2> where R1: Monoid+PartialOrd;
+
     /// Reduces the collection to one occurrence of each distinct element.
     ///
     /// # Examples
@@ -172,6 +184,10 @@ where G::Timestamp: Lattice+Ord {
         self.arrange_by_self()
             .threshold_named(name, thresh)
     }
+    fn threshold_semigroup<R2: Semigroup, F: FnMut(&K,&R1)->R2+'static>(&self, name: &str, thresh: F) -> Collection<G, K, R2> where R1: Monoid+PartialOrd {
+        self.arrange_by_self()
+            .threshold_semigroup(name, thresh)
+    }
 }
 
 impl<G: Scope, K: Data, T1, R1: Semigroup> Threshold<G, K, R1> for Arranged<G, T1>
@@ -185,6 +201,10 @@ where
         self.reduce_abelian::<_,DefaultKeyTrace<_,_,_>>(name, move |k,s,t| t.push(((), thresh(k, &s[0].1))))
             .as_collection(|k,_| k.clone())
     }
+    fn threshold_semigroup<R2: Semigroup, F: FnMut(&K,&R1)->R2+'static>(&self, name: &str, mut thresh: F) -> Collection<G, K, R2> where R1: Monoid+PartialOrd {
+        self.reduce_semigroup::<_,DefaultKeyTrace<_,_,_>>(name, move |k,s,t| t.push(((), thresh(k, &s[0].1))))
+            .as_collection(|k,_| k.clone())
+    }
 }
 
 /// Extension trait for the `count` differential dataflow method.
@@ -286,6 +306,63 @@ pub trait ReduceCore<G: Scope, K: Data, V: Data, R: Semigroup> where G::Timestam
             })
         }
 
+    /// Applies `reduce` to arranged data, for output differences without negation.
+    ///
+    /// Like `reduce_abelian`, the logic populates the desired output for each key, but the output
+    /// differences need only be a `Semigroup`. Without negation the operator cannot retract prior
+    /// outputs, and so it only supports outputs that grow as the input grows:
+    ///
+    /// * The input must be append-only: every input update must have a difference at least zero.
+    /// * Each prior output record must remain in the desired output.
+    /// * Each prior difference `prior` must move to its desired difference `desired` by addition
+    ///   alone, which requires `prior + desired == desired`. The operator emits `desired` itself as
+    ///   the update, and relies on the addition absorbing `prior`, as it does when a `MinPlus`
+    ///   minimum decreases. Differences with idempotent addition, like minima and maxima, have this
+    ///   property for any monotone logic; counts and sums do not.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an input update has a negative difference, or if the desired output requires a
+    /// retraction: if a prior output record is absent from the desired output, or if its desired
+    /// difference does not absorb its prior difference.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate timely;
+    /// extern crate differential_dataflow;
+    ///
+    /// use differential_dataflow::input::Input;
+    /// use differential_dataflow::difference::MinPlus;
+    /// use differential_dataflow::operators::reduce::ReduceCore;
+    /// use differential_dataflow::trace::implementations::ord::OrdValSpine;
+    ///
+    /// fn main() {
+    ///     ::timely::example(|scope| {
+    ///
+    ///         // the least value for each key, as a `MinPlus` difference.
+    ///         let trace =
+    ///         scope.new_collection_from(1 .. 10u64).1
+    ///              .map(|x| (x % 3, x))
+    ///              .reduce_semigroup::<_,OrdValSpine<_,_,_,_>>(
+    ///                 "Example",
+    ///                  move |_key, src, dst| dst.push(((), MinPlus::new(*src[0].0)))
+    ///              )
+    ///              .trace;
+    ///     });
+    /// }
+    /// ```
+    fn reduce_semigroup<L, T2>(&self, name: &str, logic: L) -> Arranged<G, TraceAgent<T2>>
+        where
+            R: Monoid+PartialOrd,
+            T2: Trace+TraceReader<Key=K, Time=G::Timestamp>+'static,
+            T2::Val: Data,
+            T2::R: Semigroup,
+            T2::Batch: Batch<K, T2::Val, G::Timestamp, T2::R>,
+            T2::Cursor: Cursor<K, T2::Val, G::Timestamp, T2::R>,
+            L: FnMut(&K, &[(&V, R)], &mut Vec<(T2::Val, T2::R)>)+'static
+            ;
+
     /// Solves for output updates when presented with inputs and would-be outputs.
     ///
     /// Unlike `reduce_arranged`, this method may be called with an empty `input`,
@@ -322,6 +399,20 @@ where
         self.arrange_by_key()
             .reduce_core(name, logic)
     }
+
+    fn reduce_semigroup<L, T2>(&self, name: &str, logic: L) -> Arranged<G, TraceAgent<T2>>
+        where
+            R: Monoid+PartialOrd,
+            T2: Trace+TraceReader<Key=K, Time=G::Timestamp>+'static,
+            T2::Val: Data,
+            T2::R: Semigroup,
+            T2::Batch: Batch<K, T2::Val, G::Timestamp, T2::R>,
+            T2::Cursor: Cursor<K, T2::Val, G::Timestamp, T2::R>,
+            L: FnMut(&K, &[(&V, R)], &mut Vec<(T2::Val, T2::R)>)+'static
+    {
+        self.arrange_by_key()
+            .reduce_semigroup(name, logic)
+    }
 }
 
 impl<G: Scope, K: Data, V: Data, T1, R: Semigroup> ReduceCore<G, K, V, R> for Arranged<G, T1>
@@ -331,6 +422,78 @@ where
     T1::Batch: BatchReader<K, V, G::Timestamp, R>,
     T1::Cursor: Cursor<K, V, G::Timestamp, R>,
 {
+    fn reduce_semigroup<L, T2>(&self, name: &str, mut logic: L) -> Arranged<G, TraceAgent<T2>>
+        where
+            R: Monoid+PartialOrd,
+            T2: Trace+TraceReader<Key=K, Time=G::Timestamp>+'static,
+            T2::Val: Data,
+            T2::R: Semigroup,
+            T2::Batch: Batch<K, T2::Val, G::Timestamp, T2::R>,
+            T2::Cursor: Cursor<K, T2::Val, G::Timestamp, T2::R>,
+            L: FnMut(&K, &[(&V, R)], &mut Vec<(T2::Val, T2::R)>)+'static
+    {
+        // Reject input retractions as they arrive, as they could require retracting outputs.
+        let operator = name.to_owned();
+        let stream = self.stream.inspect(move |batch| {
+            let zero = R::zero();
+            let mut cursor = batch.cursor();
+            while let Some(key) = cursor.get_key(batch) {
+                while let Some(val) = cursor.get_val(batch) {
+                    cursor.map_times(batch, |time, diff| {
+                        if diff < &zero {
+                            panic!("{}: input ({:?}, {:?}) at {:?} has difference {:?}, but inputs must be append-only", operator, key, val, time, diff);
+                        }
+                    });
+                    cursor.step_val(batch);
+                }
+                cursor.step_key(batch);
+            }
+        });
+        let checked = Arranged { stream, trace: self.trace.clone() };
+
+        let operator = name.to_owned();
+        checked.reduce_core::<_,T2>(name, move |key, input, output, change| {
+            if !input.is_empty() {
+                logic(key, input, change);
+            }
+            crate::consolidation::consolidate(change);
+
+            // Both `output` and `change` are sorted by value. Retain only those desired outputs that
+            // differ from prior outputs, compacting them to the front of `change`, and confirm that
+            // each desired difference absorbs its prior difference.
+            let mut position = 0;
+            let mut retained = 0;
+            for (val, diff) in output.iter() {
+                while position < change.len() && change[position].0 < *val {
+                    change.swap(retained, position);
+                    retained += 1;
+                    position += 1;
+                }
+                if position < change.len() && change[position].0 == *val {
+                    if change[position].1 != *diff {
+                        let mut sum = diff.clone();
+                        sum += &change[position].1;
+                        if sum != change[position].1 {
+                            panic!("{}: output {:?} for key {:?} cannot move from {:?} to {:?} without negation", operator, val, key, diff, change[position].1);
+                        }
+                        change.swap(retained, position);
+                        retained += 1;
+                    }
+                    position += 1;
+                }
+                else {
+                    panic!("{}: output {:?} for key {:?} must be retracted, which requires negation", operator, val, key);
+                }
+            }
+            while position < change.len() {
+                change.swap(retained, position);
+                retained += 1;
+                position += 1;
+            }
+            change.truncate(retained);
+        })
+    }
+
     fn reduce_core<L, T2>(&self, name: &str, mut logic: L) -> Arranged<G, TraceAgent<T2>>
         where
             T2: Trace+TraceReader<Key=K, Time=G::Timestamp>+'static,
@@ -958,12 +1121,10 @@ mod history_replay {
                             // NOTE: We only do this when we add records; it could be that there are situations
                             //       where we want to consolidate even without changes (because an initially
                             //       large collection can now be collapsed).
-                            if let Some(meet) = meet.as_ref() {
-                                for entry in &mut self.output_produced {
-                                    (entry.0).1 = (entry.0).1.join(&meet);
-                                }
+                            match meet.as_ref() {
+                                Some(meet) => ::lattice::advance_and_consolidate_data(&mut self.output_produced, ::std::slice::from_ref(meet)),
+                                None => crate::consolidation::consolidate(&mut self.output_produced),
                             }
-                            crate::consolidation::consolidate(&mut self.output_produced);
                         }
                     }
 
@@ -108,17 +108,11 @@ where
 		// We have unique ownership of the batch, and can advance times in place.
 		// We must still sort, collapse, and remove empty updates.
 
-		// We will zip throught the time leaves, calling advance on each,
-		//    then zip through the value layer, sorting and collapsing each,
+		// We will zip through the value layer, advancing, sorting, and collapsing the times of each,
 		//    then zip through the key layer, collapsing each .. ?
 
-		// 1. For each (time, diff) pair, advance the time.
-		for i in time_start .. layer.vals.vals.vals.len() {
-			layer.vals.vals.vals[i].0.advance_by(frontier);
-		}
-
-		// 2. For each `(val, off)` pair, sort the range, compact, and rewrite `off`.
-		//    This may leave `val` with an empty range; filtering happens in step 3.
+		// 1. For each `(val, off)` pair, advance the times of the range, sort and compact it, and rewrite `off`.
+		//    This may leave `val` with an empty range; filtering happens in step 2.
 		let mut write_position = time_start;
 		for i in val_start .. layer.vals.keys.len() {
 
@@ -133,8 +127,8 @@ where
 
 			let updates = &mut layer.vals.vals.vals[..];
 
-			// sort the range by the times (ignore the diffs; they will collapse).
-			let count = crate::consolidation::consolidate_slice(&mut updates[lower .. upper]);
+			// advance and sort the range by the times (ignore the diffs; they will collapse).
+			let count = ::lattice::advance_and_consolidate(&mut updates[lower .. upper], frontier);
 
 			for index in lower .. (lower + count) {
 				updates.swap(write_position, index);
@@ -144,8 +138,8 @@ where
 		layer.vals.vals.vals.truncate(write_position);
 		layer.vals.offs[layer.vals.keys.len()] = O::try_from(write_position).unwrap();
 
-		// 3. For each `(key, off)` pair, (values already sorted), filter vals, and rewrite `off`.
-		//    This may leave `key` with an empty range. Filtering happens in step 4.
+		// 2. For each `(key, off)` pair, (values already sorted), filter vals, and rewrite `off`.
+		//    This may leave `key` with an empty range. Filtering happens in step 3.
 		let mut write_position = val_start;
 		for i in key_start .. layer.keys.len() {
 
@@ -173,7 +167,7 @@ where
 		layer.vals.offs.truncate(write_position + 1);
 		layer.offs[layer.keys.len()] = O::try_from(write_position).unwrap();
 
-		// 4. Remove empty keys.
+		// 3. Remove empty keys.
 		let mut write_position = key_start;
 		for i in key_start .. layer.keys.len() {
 
@@ -436,20 +430,11 @@ where
 		let key_start = key_pos;
 		let time_start: usize = layer.offs[key_pos].try_into().unwrap();
 
-		// We will zip through the time leaves, calling advance on each,
-		//    then zip through the value layer, sorting and collapsing each,
-		//    then zip through the key layer, collapsing each .. ?
-
-		// 1. For each (time, diff) pair, advance the time.
-		for i in time_start .. layer.vals.vals.len() {
-			layer.vals.vals[i].0.advance_by(frontier);
-		}
-		// for time_diff in self.layer.vals.vals.iter_mut() {
-		// 	time_diff.0 = time_diff.0.advance_by(frontier);
-		// }
+		// We will zip through the key layer, advancing, sorting, and collapsing the times of each,
+		//    then remove keys left with empty ranges.
 
-		// 2. For each `(val, off)` pair, sort the range, compact, and rewrite `off`.
-		//    This may leave `val` with an empty range; filtering happens in step 3.
+		// 1. For each `(key, off)` pair, advance the times of the range, sort and compact it, and rewrite `off`.
+		//    This may leave `key` with an empty range; filtering happens in step 2.
 		let mut write_position = time_start;
 		for i in key_start .. layer.keys.len() {
 
@@ -464,8 +449,8 @@ where
 
 			let updates = &mut layer.vals.vals[..];
 
-			// sort the range by the times (ignore the diffs; they will collapse).
-		 	let count = crate::consolidation::consolidate_slice(&mut updates[lower .. upper]);
+			// advance and sort the range by the times (ignore the diffs; they will collapse).
+			let count = ::lattice::advance_and_consolidate(&mut updates[lower .. upper], frontier);
 
 			for index in lower .. (lower + count) {
 				updates.swap(write_position, index);
@@ -475,7 +460,7 @@ where
 		layer.vals.vals.truncate(write_position);
 		layer.offs[layer.keys.len()] = O::try_from(write_position).unwrap();
 
-		// 4. Remove empty keys.
+		// 2. Remove empty keys.
 		let mut write_position = key_start;
 		for i in key_start .. layer.keys.len() {
 
@@ -2,13 +2,15 @@
 //!
 //! Wraps a trace with a frontier so that all exposed timestamps are first advanced by the frontier.
 //! This ensures that even for traces that have been advanced, all views provided through cursors
-//! present deterministic times, independent of the compaction strategy.
+//! present deterministic times, independent of the compaction strategy. Cursors present the updates
+//! for each value advanced and consolidated, as a trace compacted to the frontier would.
 
 use timely::progress::Timestamp;
 
 use trace::{TraceReader, BatchReader, Description};
 use trace::cursor::Cursor;
-use crate::lattice::Lattice;
+use ::difference::Semigroup;
+use ::lattice::{Lattice, advance_and_consolidate};
 
 /// Wrapper to provide trace to nested scope.
 pub struct TraceFrontier<Tr>
@@ -39,7 +41,7 @@ where
     Tr::Key: 'static,
     Tr::Val: 'static,
     Tr::Time: Timestamp+Lattice,
-    Tr::R: 'static,
+    Tr::R: Semigroup+'static,
 {
     type Key = Tr::Key;
     type Val = Tr::Val;
@@ -102,6 +104,7 @@ impl<K, V, T, R, B> BatchReader<K, V, T, R> for BatchFrontier<K, V, T, R, B>
 where
     B: BatchReader<K, V, T, R>,
     T: Timestamp+Lattice,
+    R: Semigroup,
 {
     type Cursor = BatchCursorFrontier<K, V, T, R, B>;
 
@@ -132,6 +135,7 @@ pub struct CursorFrontier<K, V, T, R, C: Cursor<K, V, T, R>> {
     phantom: ::std::marker::PhantomData<(K, V, T, R)>,
     cursor: C,
     frontier: Vec<T>,
+    buffer: Vec<(T, R)>,
 }
 
 impl<K, V, T: Clone, R, C: Cursor<K, V, T, R>> CursorFrontier<K, V, T, R, C> {
@@ -140,6 +144,7 @@ impl<K, V, T: Clone, R, C: Cursor<K, V, T, R>> CursorFrontier<K, V, T, R, C> {
             phantom: ::std::marker::PhantomData,
             cursor,
             frontier: frontier.to_vec(),
+            buffer: Vec::new(),
         }
     }
 }
@@ -148,6 +153,7 @@ impl<K, V, T, R, C> Cursor<K, V, T, R> for CursorFrontier<K, V, T, R, C>
 where
     C: Cursor<K, V, T, R>,
     T: Timestamp+Lattice,
+    R: Semigroup,
 {
     type Storage = C::Storage;
 
@@ -159,13 +165,13 @@ where
 
     #[inline]
     fn map_times<L: FnMut(&T,&R)>(&mut self, storage: &Self::Storage, mut logic: L) {
-        let frontier = &self.frontier[..];
-        let mut temp: T = Default::default();
-        self.cursor.map_times(storage, |time, diff| {
-            temp.clone_from(time);
-            temp.advance_by(frontier);
-            logic(&temp, diff);
-        })
+        let buffer = &mut self.buffer;
+        buffer.clear();
+        self.cursor.map_times(storage, |time, diff| buffer.push((time.clone(), diff.clone())));
+        let count = advance_and_consolidate(&mut buffer[..], &self.frontier[..]);
+        for &(ref time, ref diff) in buffer[.. count].iter() {
+            logic(time, diff);
+        }
     }
 
     #[inline] fn step_key(&mut self, storage: &Self::Storage) { self.cursor.step_key(storage) }
@@ -185,6 +191,7 @@ pub struct BatchCursorFrontier<K, V, T, R, B: BatchReader<K, V, T, R>> {
     phantom: ::std::marker::PhantomData<(K, V, R)>,
     cursor: B::Cursor,
     frontier: Vec<T>,
+    buffer: Vec<(T, R)>,
 }
 
 impl<K, V, T: Clone, R, B: BatchReader<K, V, T, R>> BatchCursorFrontier<K, V, T, R, B> {
@@ -193,6 +200,7 @@ impl<K, V, T: Clone, R, B: BatchReader<K, V, T, R>> BatchCursorFrontier<K, V, T,
             phantom: ::std::marker::PhantomData,
             cursor,
             frontier: frontier.to_vec(),
+            buffer: Vec::new(),
         }
     }
 }
@@ -200,6 +208,7 @@ impl<K, V, T: Clone, R, B: BatchReader<K, V, T, R>> BatchCursorFrontier<K, V, T,
 impl<K, V, T, R, B: BatchReader<K, V, T, R>> Cursor<K, V, T, R> for BatchCursorFrontier<K, V, T, R, B>
 where
     T: Timestamp+Lattice,
+    R: Semigroup,
 {
     type Storage = BatchFrontier<K, V, T, R, B>;
 
@@ -211,13 +220,13 @@ where
 
     #[inline]
     fn map_times<L: FnMut(&T,&R)>(&mut self, storage: &Self::Storage, mut logic: L) {
-        let frontier = &self.frontier[..];
-        let mut temp: T = Default::default();
-        self.cursor.map_times(&storage.batch, |time, diff| {
-            temp.clone_from(time);
-            temp.advance_by(frontier);
-            logic(&temp, diff);
-        })
+        let buffer = &mut self.buffer;
+        buffer.clear();
+        self.cursor.map_times(&storage.batch, |time, diff| buffer.push((time.clone(), diff.clone())));
+        let count = advance_and_consolidate(&mut buffer[..], &self.frontier[..]);
+        for &(ref time, ref diff) in buffer[.. count].iter() {
+            logic(time, diff);
+        }
     }
 
     #[inline] fn step_key(&mut self, storage: &Self::Storage) { self.cursor.step_key(&storage.batch) }
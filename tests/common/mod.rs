@@ -0,0 +1,144 @@
+//! Random edge changes and harnesses shared by the graph algorithm tests.
+//!
+//! Each test applies rounds of random edge changes both to an incrementally maintained computation and
+//! to a reference computed from scratch on each round's edges, and compares the changes to their outputs.
+
+// Each test uses only some of the helpers.
+#![allow(dead_code)]
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use rand::{Rng, SeedableRng, StdRng};
+
+use timely::Configuration;
+use timely::communication::Allocator;
+use timely::worker::Worker;
+use timely::dataflow::scopes::Child;
+use timely::dataflow::operators::Capture;
+use timely::dataflow::operators::capture::Extract;
+
+use differential_dataflow::{Collection, ExchangeData, Hashable};
+use differential_dataflow::input::Input;
+use differential_dataflow::operators::Consolidate;
+use differential_dataflow::consolidation::consolidate_updates;
+
+pub type Node = usize;
+pub type Edge = (Node, Node);
+
+/// Changes to a collection, as `(data, round, diff)` triples.
+pub type Changes<D> = Vec<(D, usize, isize)>;
+
+/// The scope in which `differential` builds computations.
+pub type Scope<'a> = Child<'a, Worker<Allocator>, usize>;
+
+/// Rounds of random edge insertions and deletions of present edges, each edge present at most once.
+///
+/// The first round inserts up to `initial` edges between `nodes` nodes, and each later round inserts up
+/// to `inserts` edges and deletes up to `deletes` present edges, fewer if fewer are present.
+pub fn random_changes(rounds: usize, nodes: usize, initial: usize, inserts: usize, deletes: usize) -> Vec<Vec<(Edge, isize)>> {
+
+    let seed: &[_] = &[1, 2, 3, 4];
+    let mut rng: StdRng = SeedableRng::from_seed(seed);
+
+    let mut present = Vec::new();
+    let mut changes = Vec::new();
+    for round in 0 .. rounds {
+        let mut round_changes = Vec::new();
+        for _ in 0 .. if round == 0 { initial } else { inserts } {
+            let edge = (rng.gen_range(0, nodes), rng.gen_range(0, nodes));
+            if !present.contains(&edge) {
+                present.push(edge);
+                round_changes.push((edge, 1));
+            }
+        }
+        if round > 0 {
+            for _ in 0 .. deletes {
+                if !present.is_empty() {
+                    let index = rng.gen_range(0, present.len());
+                    round_changes.push((present.remove(index), -1));
+                }
+            }
+        }
+        changes.push(round_changes);
+    }
+    changes
+}
+
+/// The changes in each round to the output of `logic`, computed from scratch on each round's edges.
+///
+/// The output of `logic` is a list of records and their multiplicities.
+pub fn from_scratch<D, I, F>(changes: &[Vec<(Edge, isize)>], mut logic: F) -> Changes<D>
+where
+    D: Ord+Clone,
+    I: IntoIterator<Item=(D, isize)>,
+    F: FnMut(&HashSet<Edge>)->I,
+{
+    let mut edges = HashSet::new();
+    let mut prior = BTreeMap::new();
+    let mut results = Vec::new();
+    for (round, round_changes) in changes.iter().enumerate() {
+
+        for &(edge, diff) in round_changes.iter() {
+            if diff > 0 { edges.insert(edge); } else { edges.remove(&edge); }
+        }
+
+        let mut next = BTreeMap::new();
+        for (datum, diff) in logic(&edges) {
+            *next.entry(datum).or_insert(0) += diff;
+        }
+
+        for (datum, &diff) in next.iter() {
+            results.push((datum.clone(), round, diff));
+        }
+        for (datum, &diff) in prior.iter() {
+            results.push((datum.clone(), round, -diff));
+        }
+        prior = next;
+    }
+    consolidate_updates(&mut results);
+    results
+}
+
+/// The changes in each round to the output of `logic`, maintained incrementally.
+///
+/// The computation is given the edges, changed by one worker in each round, and a collection of roots
+/// containing `roots` from the first round.
+pub fn differential<D, F>(changes: &[Vec<(Edge, isize)>], roots: &[Node], config: Configuration, logic: F) -> Changes<D>
+where
+    D: ExchangeData+Hashable,
+    F: for<'a> Fn(&Collection<Scope<'a>, Edge>, &Collection<Scope<'a>, Node>)->Collection<Scope<'a>, D>+Send+Sync+'static,
+{
+    let changes = changes.to_vec();
+    let roots = roots.to_vec();
+    let (send, recv) = ::std::sync::mpsc::channel();
+    let send = Arc::new(Mutex::new(send));
+
+    timely::execute(config, move |worker| {
+
+        let send = send.lock().unwrap().clone();
+        let (mut root_input, mut edge_input) = worker.dataflow::<usize,_,_>(|scope| {
+            let (root_input, roots) = scope.new_collection();
+            let (edge_input, edges) = scope.new_collection();
+            logic(&edges, &roots).consolidate().inner.capture_into(send);
+            (root_input, edge_input)
+        });
+
+        if worker.index() == 0 {
+            for &root in roots.iter() {
+                root_input.insert(root);
+            }
+            for (round, round_changes) in changes.iter().enumerate() {
+                edge_input.advance_to(round);
+                for &(edge, diff) in round_changes.iter() {
+                    edge_input.update(edge, diff);
+                }
+            }
+        }
+
+    }).unwrap();
+
+    let mut results = recv.extract().into_iter().flat_map(|(_, list)| list.into_iter()).collect::<Vec<_>>();
+    consolidate_updates(&mut results);
+    results
+}
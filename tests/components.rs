@@ -0,0 +1,42 @@
+extern crate rand;
+extern crate timely;
+extern crate differential_dataflow;
+
+mod common;
+
+use std::collections::{HashMap, HashSet};
+
+use timely::Configuration;
+
+use differential_dataflow::algorithms::graphs::components::{connected_components, connected_components_jumping};
+
+use common::{Node, Edge, random_changes, from_scratch, differential};
+
+/// The (node, label) pairs of `edges`, from union-find.
+fn components(edges: &HashSet<Edge>) -> Vec<((Node, Node), isize)> {
+
+    fn find(parent: &mut HashMap<Node, Node>, node: Node) -> Node {
+        let next = *parent.entry(node).or_insert(node);
+        if next == node { node } else { let root = find(parent, next); parent.insert(node, root); root }
+    }
+
+    // union by smaller root, so that each root is the smallest node of its component.
+    let mut parent = HashMap::new();
+    for &(src, dst) in edges.iter() {
+        let (root1, root2) = (find(&mut parent, src), find(&mut parent, dst));
+        if root1 < root2 { parent.insert(root2, root1); }
+        if root2 < root1 { parent.insert(root1, root2); }
+    }
+    let nodes = parent.keys().cloned().collect::<Vec<_>>();
+    nodes.into_iter().map(|node| ((node, find(&mut parent, node)), 1)).collect()
+}
+
+#[test]
+fn components_match_sequential() {
+
+    let changes = random_changes(10, 50, 40, 4, 4);
+    let expected = from_scratch(&changes, components);
+
+    assert_eq!(differential(&changes, &[], Configuration::Process(2), |edges, _| connected_components(edges)), expected);
+    assert_eq!(differential(&changes, &[], Configuration::Process(2), |edges, _| connected_components_jumping(edges)), expected);
+}
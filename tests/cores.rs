@@ -0,0 +1,92 @@
+extern crate rand;
+extern crate timely;
+extern crate differential_dataflow;
+
+mod common;
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use timely::Configuration;
+
+use differential_dataflow::algorithms::graphs::cores::{k_core, core_numbers};
+use differential_dataflow::algorithms::graphs::degrees::{out_degrees, in_degrees, distribution};
+
+use common::{Node, Edge, random_changes, from_scratch, differential};
+
+/// The core number of each node of the undirected graph of `edges`, and the edges of its 3-core.
+///
+/// Nodes are peeled in order of least remaining degree, and the core number of each is the largest
+/// such degree seen so far.
+fn cores(edges: &HashSet<Edge>) -> (BTreeSet<(Node, usize)>, BTreeSet<Edge>) {
+
+    let mut neighbors = BTreeMap::new();
+    for &(src, dst) in edges.iter().filter(|e| e.0 != e.1) {
+        neighbors.entry(src).or_insert(BTreeSet::new()).insert(dst);
+        neighbors.entry(dst).or_insert(BTreeSet::new()).insert(src);
+    }
+
+    let mut remaining = neighbors.clone();
+    let mut cores = BTreeSet::new();
+    let mut k = 0;
+    loop {
+        let node = match remaining.iter().min_by_key(|&(n, adj)| (adj.len(), *n)) {
+            Some((&node, _)) => node,
+            None => break,
+        };
+        let adjacent = remaining.remove(&node).unwrap();
+        k = ::std::cmp::max(k, adjacent.len());
+        cores.insert((node, k));
+        for other in adjacent.iter() {
+            remaining.get_mut(other).unwrap().remove(&node);
+        }
+    }
+
+    let mut core3 = BTreeSet::new();
+    for &(node, core) in cores.iter() {
+        if core >= 3 {
+            for &other in neighbors[&node].iter() {
+                if cores.iter().any(|&(n, c)| n == other && c >= 3) { core3.insert((node, other)); }
+            }
+        }
+    }
+    (cores, core3)
+}
+
+#[test]
+fn cores_match_sequential() {
+
+    let changes = random_changes(10, 25, 60, 5, 5);
+
+    let expected_cores = from_scratch(&changes, |edges| cores(edges).0.into_iter().map(|x| (x, 1)));
+    let expected_core3 = from_scratch(&changes, |edges| cores(edges).1.into_iter().map(|x| (x, 1)));
+    let expected_dist = from_scratch(&changes, |edges| {
+        let mut degrees = BTreeMap::new();
+        for &(src, _dst) in edges.iter() { *degrees.entry(src).or_insert(0isize) += 1; }
+        let mut counts = BTreeMap::new();
+        for (_, degree) in degrees { *counts.entry(degree).or_insert(0isize) += 1; }
+        counts.into_iter().map(|x| (x, 1)).collect::<Vec<_>>()
+    });
+
+    assert_eq!(differential(&changes, &[], Configuration::Process(2), |edges, _| core_numbers(edges)), expected_cores);
+    assert_eq!(differential(&changes, &[], Configuration::Process(2), |edges, _| k_core(edges, 3)), expected_core3);
+    assert_eq!(differential(&changes, &[], Configuration::Process(2), |edges, _| distribution(&out_degrees(edges))), expected_dist);
+}
+
+#[test]
+fn degrees_match_sequential() {
+
+    let changes = random_changes(10, 25, 60, 5, 5);
+
+    // the number of edges leaving or entering each node with such edges.
+    let degrees = |edges: &HashSet<Edge>, outgoing: bool| {
+        let mut degrees = BTreeMap::new();
+        for &(src, dst) in edges.iter() { *degrees.entry(if outgoing { src } else { dst }).or_insert(0isize) += 1; }
+        degrees.into_iter().map(|x| (x, 1)).collect::<Vec<_>>()
+    };
+
+    let expected_out = from_scratch(&changes, |edges| degrees(edges, true));
+    let expected_in = from_scratch(&changes, |edges| degrees(edges, false));
+
+    assert_eq!(differential(&changes, &[], Configuration::Process(2), |edges, _| out_degrees(edges)), expected_out);
+    assert_eq!(differential(&changes, &[], Configuration::Process(2), |edges, _| in_degrees(edges)), expected_in);
+}
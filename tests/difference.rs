@@ -0,0 +1,49 @@
+extern crate differential_dataflow;
+
+use differential_dataflow::difference::{Semigroup, Rational};
+
+#[test]
+fn rational_extreme_values() {
+
+    let min = i128::min_value();
+    let max = i128::max_value();
+
+    // the most negative numerator is kept, and reduced with the denominator.
+    let rational = Rational::new(min, 1);
+    assert_eq!((rational.numer(), rational.denom()), (min, 1));
+    let rational = Rational::new(min, 2);
+    assert_eq!((rational.numer(), rational.denom()), (min / 2, 1));
+    let rational = Rational::new(min, min);
+    assert_eq!((rational.numer(), rational.denom()), (1, 1));
+    let rational = Rational::new(0, min);
+    assert_eq!((rational.numer(), rational.denom()), (0, 1));
+
+    // a most negative denominator moves its sign to the numerator once reduced.
+    let rational = Rational::new(2, min);
+    assert_eq!((rational.numer(), rational.denom()), (-1, -(min / 2)));
+    let rational = Rational::new(max, -max);
+    assert_eq!((rational.numer(), rational.denom()), (-1, 1));
+
+    let mut total = Rational::new(min, 1);
+    total += &Rational::new(max, 1);
+    total += &Rational::new(1, 1);
+    assert!(total.is_zero());
+}
+
+#[test]
+#[should_panic(expected = "Rational overflow")]
+fn rational_unrepresentable_numerator() {
+    Rational::new(i128::min_value(), -1);
+}
+
+#[test]
+#[should_panic(expected = "Rational overflow")]
+fn rational_unrepresentable_denominator() {
+    Rational::new(1, i128::min_value());
+}
+
+#[test]
+#[should_panic(expected = "Rational overflow")]
+fn rational_unrepresentable_negation() {
+    let _ = -Rational::new(i128::min_value(), 1);
+}
@@ -0,0 +1,116 @@
+extern crate rand;
+extern crate timely;
+extern crate differential_dataflow;
+
+mod common;
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use timely::Configuration;
+
+use differential_dataflow::algorithms::graphs::reachability::{reachable, transitive_closure};
+use differential_dataflow::algorithms::graphs::dominators::{dominators, dominator_tree};
+
+use common::{Node, Edge, random_changes, from_scratch, differential};
+
+/// Nodes reachable from `sources` without passing through `avoided`.
+fn reach(edges: &HashSet<Edge>, sources: &[Node], avoided: Option<Node>) -> BTreeSet<Node> {
+    let mut reached = sources.iter().cloned().filter(|&n| Some(n) != avoided).collect::<BTreeSet<_>>();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for &(src, dst) in edges.iter() {
+            if reached.contains(&src) && Some(dst) != avoided && reached.insert(dst) {
+                changed = true;
+            }
+        }
+    }
+    reached
+}
+
+/// The pairs `(source, node)` of each source and a node reachable from it.
+fn closure(edges: &HashSet<Edge>, sources: &[Node]) -> BTreeSet<Edge> {
+    let mut closure = BTreeSet::new();
+    for &source in sources.iter() {
+        for node in reach(edges, &[source], None) {
+            closure.insert((source, node));
+        }
+    }
+    closure
+}
+
+/// The strict dominators of each node reachable from `sources`, with all sources as entries.
+fn strict_dominators(edges: &HashSet<Edge>, sources: &[Node]) -> BTreeMap<Node, Vec<Node>> {
+
+    // dominators by definition: `d` dominates `n` if removing `d` makes `n` unreachable.
+    let nodes = reach(edges, sources, None);
+    let mut dominators = BTreeMap::new();
+    for &avoided in nodes.iter() {
+        let remaining = reach(edges, sources, Some(avoided));
+        for &node in nodes.iter().filter(|n| !remaining.contains(n) && **n != avoided) {
+            dominators.entry(node).or_insert(Vec::new()).push(avoided);
+        }
+    }
+    dominators
+}
+
+/// The pairs `(node, idom)` of each node and its immediate dominator.
+fn tree(edges: &HashSet<Edge>, sources: &[Node]) -> BTreeSet<Edge> {
+
+    // the immediate dominator is dominated by all other strict dominators.
+    let dominators = strict_dominators(edges, sources);
+    let mut tree = BTreeSet::new();
+    for (&node, strict) in dominators.iter() {
+        for &candidate in strict.iter() {
+            let candidate_dominators = dominators.get(&candidate).cloned().unwrap_or(Vec::new());
+            if strict.iter().all(|d| *d == candidate || candidate_dominators.contains(d)) {
+                tree.insert((node, candidate));
+            }
+        }
+    }
+    tree
+}
+
+#[test]
+fn dominators_match_sequential() {
+
+    let changes = random_changes(10, 20, 30, 3, 3);
+    let sources = vec![0, 1];
+
+    let expected_reach = from_scratch(&changes, |edges| reach(edges, &sources, None).into_iter().map(|x| (x, 1)));
+    let expected_closure = from_scratch(&changes, |edges| closure(edges, &sources).into_iter().map(|x| (x, 1)));
+    let expected_dominators = from_scratch(&changes, |edges| {
+        let nodes = reach(edges, &sources, None);
+        let strict = strict_dominators(edges, &sources);
+        let mut pairs = nodes.iter().map(|&node| ((node, node), 1)).collect::<Vec<_>>();
+        for (&node, dominators) in strict.iter() {
+            pairs.extend(dominators.iter().map(|&dominator| ((node, dominator), 1)));
+        }
+        pairs
+    });
+    let expected_tree = from_scratch(&changes, |edges| tree(edges, &sources).into_iter().map(|x| (x, 1)));
+
+    assert_eq!(differential(&changes, &sources, Configuration::Process(2), |edges, roots| reachable(edges, roots)), expected_reach);
+    assert_eq!(differential(&changes, &sources, Configuration::Process(2), |edges, roots| transitive_closure(edges, roots)), expected_closure);
+    assert_eq!(differential(&changes, &sources, Configuration::Process(2), |edges, roots| dominators(edges, roots)), expected_dominators);
+    assert_eq!(differential(&changes, &sources, Configuration::Process(2), |edges, roots| dominator_tree(edges, roots)), expected_tree);
+}
+
+#[test]
+fn dominators_multiple_entries() {
+
+    // entries 0 and 5 meet at 2, which 3 and 4 then pass through; 6 and 7 are unreachable.
+    let changes = vec![vec![((0, 1), 1), ((1, 2), 1), ((5, 2), 1), ((2, 3), 1), ((3, 4), 1), ((6, 7), 1)]];
+    let entries = [0, 5];
+
+    let nodes = differential(&changes, &entries, Configuration::Thread, |edges, roots| reachable(edges, roots));
+    assert_eq!(nodes, vec![(0, 0, 1), (1, 0, 1), (2, 0, 1), (3, 0, 1), (4, 0, 1), (5, 0, 1)]);
+
+    // neither entry dominates 2, as each reaches it without the other.
+    let pairs = differential(&changes, &entries, Configuration::Thread, |edges, roots| dominators(edges, roots));
+    let expected = vec![(0, 0), (1, 0), (1, 1), (2, 2), (3, 2), (3, 3), (4, 2), (4, 3), (4, 4), (5, 5)];
+    assert_eq!(pairs, expected.into_iter().map(|pair| (pair, 0, 1)).collect::<Vec<_>>());
+
+    let tree = differential(&changes, &entries, Configuration::Thread, |edges, roots| dominator_tree(edges, roots));
+    assert_eq!(tree, vec![((1, 0), 0, 1), ((3, 2), 0, 1), ((4, 3), 0, 1)]);
+}
@@ -0,0 +1,368 @@
+extern crate timely;
+extern crate differential_dataflow;
+
+use timely::dataflow::operators::Capture;
+use timely::dataflow::operators::capture::Extract;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::operators::{Iterate, Consolidate};
+
+fn halve(x: u32) -> u32 { if x % 2 == 0 { x / 2 } else { x } }
+
+#[test]
+fn iterate_bounded_incomplete() {
+
+    let (results, residuals) = timely::example(|scope| {
+
+        let (result, residual) =
+        scope.new_collection_from(1 .. 10u32).1
+             .iterate_bounded(2, |values| values.map(halve).consolidate());
+
+        (result.consolidate().inner.capture(), residual.consolidate().inner.capture())
+    });
+
+    let mut results = results.extract().into_iter().flat_map(|(_, data)| data).map(|(x,_,r)| (x,r)).collect::<Vec<_>>();
+    let mut residuals = residuals.extract().into_iter().flat_map(|(_, data)| data).map(|(x,_,r)| (x,r)).collect::<Vec<_>>();
+    results.sort();
+    residuals.sort();
+
+    // two applications of `halve`, and the change the next round would have seen.
+    assert_eq!(results, vec![(1, 3), (2, 1), (3, 2), (5, 1), (7, 1), (9, 1)]);
+    assert_eq!(residuals, vec![(1, 1), (4, -1)]);
+}
+
+#[test]
+fn iterate_bounded_complete() {
+
+    let (bounded, unbounded, residuals) = timely::example(|scope| {
+
+        let values = scope.new_collection_from(1 .. 100u32).1;
+
+        let (result, residual) = values.iterate_bounded(10, |values| values.map(halve).consolidate());
+        let expected = values.iterate(|values| values.map(halve).consolidate());
+
+        (result.consolidate().inner.capture(), expected.consolidate().inner.capture(), residual.consolidate().inner.capture())
+    });
+
+    let mut bounded = bounded.extract().into_iter().flat_map(|(_, data)| data).map(|(x,_,r)| (x,r)).collect::<Vec<_>>();
+    let mut unbounded = unbounded.extract().into_iter().flat_map(|(_, data)| data).map(|(x,_,r)| (x,r)).collect::<Vec<_>>();
+    bounded.sort();
+    unbounded.sort();
+
+    assert_eq!(bounded, unbounded);
+    assert!(residuals.extract().into_iter().all(|(_, data)| data.is_empty()));
+}
+
+#[test]
+fn iterate_bounded_zero_rounds() {
+
+    let results = timely::example(|scope| {
+        let (result, _residual) =
+        scope.new_collection_from(vec![4u32, 8]).1
+             .iterate_bounded(0, |values| values.map(halve).consolidate());
+        result.consolidate().inner.capture()
+    });
+
+    let mut results = results.extract().into_iter().flat_map(|(_, data)| data).map(|(x,_,r)| (x,r)).collect::<Vec<_>>();
+    results.sort();
+
+    // `logic` is applied once, even without rounds to feed back.
+    assert_eq!(results, vec![(2, 1), (4, 1)]);
+}
+
+#[test]
+fn instrument_reports_oscillation() {
+
+    use std::rc::Rc;
+    use std::cell::RefCell;
+
+    use timely::order::Product;
+    use timely::dataflow::Scope;
+
+    use differential_dataflow::operators::iterate::Variable;
+    use differential_dataflow::logging::{DifferentialEvent, IterationEvent, OscillationEvent};
+
+    timely::execute(timely::Configuration::Thread, move |worker| {
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let events_clone = events.clone();
+        worker.log_register().insert::<DifferentialEvent,_>("differential/iterate", move |_time, data| {
+            events_clone.borrow_mut().extend(data.drain(..).map(|(_, _, event)| event));
+        });
+
+        worker.dataflow::<u32,_,_>(|scope| {
+
+            let toggle = scope.new_collection_from(vec![0u32]).1;
+
+            // each round replaces the record with the other, undoing the prior round.
+            scope.iterative::<u64,_,_>(|nested| {
+                let summary = Product::new(Default::default(), 1);
+                let variable = Variable::new_bounded_from(toggle.enter(nested), summary, 4).instrument("toggle", 1);
+                let result = variable.map(|x| 1 - x);
+                variable.set(&result).leave()
+            });
+        });
+
+        while worker.step() { }
+        worker.log_register().flush();
+
+        let events = events.borrow();
+        assert!(events.contains(&DifferentialEvent::Iteration(IterationEvent {
+            name: "toggle".to_owned(),
+            round: 1,
+            updates: 2,
+        })));
+        assert!(events.contains(&DifferentialEvent::Oscillation(OscillationEvent {
+            name: "toggle".to_owned(),
+            round: 2,
+            oscillating: 2,
+            sample: vec!["0".to_owned()],
+        })));
+    }).unwrap();
+}
+
+#[test]
+fn semi_naive_matches_naive() {
+
+    use differential_dataflow::operators::{Join, JoinCore, Threshold};
+    use differential_dataflow::operators::arrange::ArrangeByKey;
+    use differential_dataflow::operators::iterate::SemiNaive;
+
+    timely::execute(timely::Configuration::Thread, move |worker| {
+
+        let mut input = worker.dataflow::<u32,_,_>(|scope| {
+
+            let (input, edges) = scope.new_collection::<(u32, u32), isize>();
+
+            // reach(x, z) :- reach(x, y), edge(y, z), with reach keyed by y.
+            let linear =
+            edges.map(|(x, y)| (y, x))
+                 .semi_naive(|delta, _total| {
+                     let edges = edges.enter(&delta.scope()).arrange_by_key();
+                     delta.arrange_by_key()
+                          .join_core(&edges, |_y, &x, &z| Some((z, x)))
+                 });
+
+            let reach =
+            edges.map(|(x, y)| (y, x))
+                 .iterate(|reach| {
+                     let edges = edges.enter(&reach.scope());
+                     reach.join_map(&edges, |_y, &x, &z| (z, x))
+                          .concat(reach)
+                          .distinct()
+                 });
+
+            linear.assert_eq(&reach);
+
+            // same(x, z) :- same(y, x), same(y, z), over edges in both directions.
+            let symmetric = edges.map(|(x, y)| (y, x)).concat(&edges);
+            let nonlinear =
+            symmetric.semi_naive(|delta, total| {
+                delta.arrange_by_key()
+                     .join_core(total, |_y, &x, &z| vec![(x, z), (z, x)])
+            });
+
+            let same =
+            symmetric.iterate(|same| {
+                same.join_map(same, |_y, &x, &z| (x, z))
+                    .concat(same)
+                    .distinct()
+            });
+
+            nonlinear.assert_eq(&same);
+            input
+        });
+
+        // a cycle and a chain, which are then joined, broken, and repaired.
+        input.insert((0, 1));
+        input.insert((1, 2));
+        input.insert((2, 0));
+        input.insert((3, 4));
+        input.insert((4, 5));
+        input.advance_to(1);
+        input.insert((2, 3));
+        input.insert((2, 3));
+        input.advance_to(2);
+        input.remove((1, 2));
+        input.remove((4, 5));
+        input.advance_to(3);
+        input.insert((1, 2));
+        input.remove((2, 3));
+        input.remove((2, 3));
+        input.insert((5, 5));
+    }).unwrap();
+}
+
+#[test]
+fn program_mutual_recursion() {
+
+    let (evens, odds) = timely::example(|scope| {
+
+        use differential_dataflow::operators::iterate::Program;
+
+        let seeds = scope.new_collection_from(vec![0u32]).1;
+
+        let mut program = Program::new();
+        program.relation_from("evens", &seeds);
+        program.relation("odds");
+        program.rule("odds", |body| body.get("evens").map(|x| x + 1).filter(|x| *x < 10));
+        program.rule("evens", |body| body.get("odds").map(|x| x + 1).filter(|x| *x < 10));
+
+        let results = program.complete(scope).expect("malformed program");
+        (results["evens"].consolidate().inner.capture(), results["odds"].consolidate().inner.capture())
+    });
+
+    let mut evens = evens.extract().into_iter().flat_map(|(_, data)| data).map(|(x,_,r)| (x,r)).collect::<Vec<_>>();
+    let mut odds = odds.extract().into_iter().flat_map(|(_, data)| data).map(|(x,_,r)| (x,r)).collect::<Vec<_>>();
+    evens.sort();
+    odds.sort();
+
+    assert_eq!(evens, vec![(0, 1), (2, 1), (4, 1), (6, 1), (8, 1)]);
+    assert_eq!(odds, vec![(1, 1), (3, 1), (5, 1), (7, 1), (9, 1)]);
+}
+
+#[test]
+fn program_inputs_and_entered_collections() {
+
+    let reach = timely::example(|scope| {
+
+        use differential_dataflow::operators::Join;
+        use differential_dataflow::operators::iterate::Program;
+
+        let edges = scope.new_collection_from(vec![(0u32, 1u32), (1, 2), (3, 4)]).1;
+        let roots = scope.new_collection_from(vec![(0u32, 0u32)]).1;
+
+        // reach(y, y) :- root(y, y).
+        // reach(y, y) :- reach(x, x), edge(x, y).
+        let mut program = Program::new();
+        program.input("root", &roots);
+        program.relation("reach");
+        program.rule("reach", |body| body.get("root").clone());
+        program.rule("reach", move |body| body.get("reach").join_map(&body.enter(&edges), |_x, _, &y| (y, y)));
+
+        let results = program.complete(scope).expect("malformed program");
+        assert!(!results.contains_key("root"));
+        results["reach"].consolidate().inner.capture()
+    });
+
+    let mut reach = reach.extract().into_iter().flat_map(|(_, data)| data).map(|(x,_,r)| (x,r)).collect::<Vec<_>>();
+    reach.sort();
+
+    assert_eq!(reach, vec![((0, 0), 1), ((1, 1), 1), ((2, 2), 1)]);
+}
+
+#[test]
+fn program_undefined_relation() {
+
+    let (repeated, undeclared, input) = timely::example(|scope| {
+
+        use differential_dataflow::operators::iterate::Program;
+
+        let edges = scope.new_collection_from(vec![(0u32, 1u32)]).1;
+
+        let mut program = Program::<_, (u32, u32)>::new();
+        program.relation("reach");
+        program.relation("reach");
+        let repeated = program.complete(scope).err();
+
+        let mut program = Program::<_, (u32, u32)>::new();
+        program.relation("reach");
+        program.rule("reach", |body| body.get("reach").clone());
+        program.rule("reached", |body| body.get("reach").clone());
+        let undeclared = program.complete(scope).err();
+
+        let mut program = Program::new();
+        program.input("edge", &edges);
+        program.relation("reach");
+        program.rule("reach", |body| body.get("edge").clone());
+        program.rule("edge", |body| body.get("reach").clone());
+        let input = program.complete(scope).err();
+
+        (repeated, undeclared, input)
+    });
+
+    let repeated = repeated.expect("program should be rejected");
+    assert!(repeated.contains("declared more than once"));
+    assert!(repeated.contains("has no rules"));
+    assert!(undeclared.expect("program should be rejected").contains("\"reached\" has rules but was not declared"));
+    assert!(input.expect("program should be rejected").contains("cannot also be defined by rules"));
+}
+
+#[test]
+fn stratified_negation_across_strata() {
+
+    let (strata, unreached, stranded) = timely::example(|scope| {
+
+        use differential_dataflow::operators::Join;
+        use differential_dataflow::operators::iterate::Stratified;
+
+        let edges = scope.new_collection_from(vec![(0u32, 1u32), (1, 2), (3, 4), (4, 3), (5, 0)]).1;
+        let nodes = scope.new_collection_from((0 .. 6u32).map(|x| (x, x))).1;
+        let roots = scope.new_collection_from(vec![(0u32, 0u32)]).1;
+
+        let mut program = Stratified::new();
+        program.input("edge", &edges);
+        program.input("node", &nodes);
+        program.input("root", &roots);
+        // reached(x, x) :- root(x, x).
+        program.rule("reached", &["root"], &[], |body| body.get("root").clone());
+        // reached(y, y) :- reached(x, x), edge(x, y).
+        program.rule("reached", &["reached", "edge"], &[], |body| {
+            body.get("reached")
+                .join_map(body.get("edge"), |_x, _, &y| (y, y))
+        });
+        // unreached(x, x) :- node(x, x), !reached(x, x).
+        program.rule("unreached", &["node"], &["reached"], |body| {
+            body.get("node").concat(&body.get("reached").negate())
+        });
+        // stranded(x, y) :- unreached(x, x), edge(x, y).
+        program.rule("stranded", &["unreached", "edge"], &[], |body| {
+            body.get("unreached")
+                .join_map(body.get("edge"), |&x, _, &y| (x, y))
+        });
+
+        let strata = program.strata();
+        let results = program.build(scope).expect("program should be stratifiable");
+
+        (strata, results["unreached"].consolidate().inner.capture(), results["stranded"].consolidate().inner.capture())
+    });
+
+    let mut unreached = unreached.extract().into_iter().flat_map(|(_, data)| data).map(|(x,_,r)| (x,r)).collect::<Vec<_>>();
+    let mut stranded = stranded.extract().into_iter().flat_map(|(_, data)| data).map(|(x,_,r)| (x,r)).collect::<Vec<_>>();
+    unreached.sort();
+    stranded.sort();
+
+    assert_eq!(strata, Ok(vec![vec!["reached".to_owned()], vec!["unreached".to_owned()], vec!["stranded".to_owned()]]));
+    assert_eq!(unreached, vec![((3, 3), 1), ((4, 4), 1), ((5, 5), 1)]);
+    assert_eq!(stranded, vec![((3, 4), 1), ((4, 3), 1), ((5, 0), 1)]);
+}
+
+#[test]
+fn stratified_rejects_recursive_negation() {
+
+    let (strata, error) = timely::example(|scope| {
+
+        use differential_dataflow::operators::iterate::Stratified;
+
+        let edges = scope.new_collection_from(vec![(0u32, 1u32)]).1;
+
+        // reach depends on edge, and unreached negates reach from a later stratum.
+        let mut program = Stratified::new();
+        program.input("edge", &edges);
+        program.rule("reach", &["edge"], &[], |body| body.get("edge").clone());
+        program.rule("reach", &["reach"], &[], |body| body.get("reach").clone());
+        program.rule("unreached", &["edge"], &["reach"], |body| body.get("edge").concat(&body.get("reach").negate()));
+        let strata = program.strata();
+
+        // win(x) :- move(x, y), !win(y).
+        let mut program = Stratified::new();
+        program.input("move", &edges);
+        program.rule("win", &["move"], &["win"], |body| body.get("move").clone());
+        let error = program.build(scope).err();
+
+        (strata, error)
+    });
+
+    assert_eq!(strata, Ok(vec![vec!["reach".to_owned()], vec!["unreached".to_owned()]]));
+    assert!(error.expect("program should be rejected").contains("through recursion"));
+}
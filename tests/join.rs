@@ -101,4 +101,394 @@ fn join_scaling(scale: u64) {
 
     let extracted = data.extract();
     assert_eq!(extracted.len(), 0);
-}
\ No newline at end of file
+}
+
+#[test]
+fn join_min_plus() {
+
+    use differential_dataflow::difference::MinPlus;
+
+    let data = timely::example(|scope| {
+        // path lengths to a midpoint, and from the midpoint to a destination.
+        let col1 = vec![((0,'a'), Default::default(),MinPlus::new(3)),((0,'b'), Default::default(),MinPlus::new(5))].into_iter().to_stream(scope).as_collection();
+        let col2 = vec![((0,'z'), Default::default(),MinPlus::new(4))].into_iter().to_stream(scope).as_collection();
+
+        // should produce the combined lengths `7` and `9`, and retain only the shorter.
+        col1.join_map(&col2, |_k,_v1,v2| *v2).consolidate().inner.capture()
+    });
+
+    let extracted = data.extract();
+    assert_eq!(extracted.len(), 1);
+    assert_eq!(extracted[0].1, vec![('z', Default::default(), MinPlus::new(7))]);
+}
+
+#[test]
+fn join_partitioned() {
+
+    use std::sync::{Arc, Mutex};
+    use timely::Configuration;
+    use differential_dataflow::input::Input;
+    use differential_dataflow::hashable::RangePartitioner;
+
+    let results = Arc::new(Mutex::new(Vec::new()));
+    let shared = results.clone();
+
+    timely::execute(Configuration::Process(2), move |worker| {
+        let index = worker.index();
+        let shared = shared.clone();
+        worker.dataflow::<u32,_,_>(move |scope| {
+            let data = if index == 0 { vec![(1, 'a'), (7, 'b')] } else { vec![(3, 'c'), (9, 'd')] };
+            let col1 = scope.new_collection_from(data.clone()).1;
+            let col2 = scope.new_collection_from(data).1.map(|(k, v)| (k, v.to_ascii_uppercase()));
+
+            // keys below five join at worker zero, and the others at worker one.
+            col1.join_partitioned(&col2, RangePartitioner::new(vec![5]))
+                .inspect(move |x| shared.lock().unwrap().push((index, (x.0).0, x.2)));
+        });
+    }).unwrap();
+
+    let mut results = results.lock().unwrap().clone();
+    results.sort();
+    assert_eq!(results, vec![(0, 1, 1), (0, 3, 1), (1, 7, 1), (1, 9, 1)]);
+}
+
+#[test]
+fn join_skewed() {
+
+    use std::sync::{Arc, Mutex};
+    use timely::Configuration;
+    use differential_dataflow::input::Input;
+    use differential_dataflow::operators::join::JoinSkewed;
+
+    let results = Arc::new(Mutex::new(Vec::new()));
+    let shared = results.clone();
+
+    timely::execute(Configuration::Process(3), move |worker| {
+        let index = worker.index();
+        let shared = shared.clone();
+        let (mut facts, mut dims) = worker.dataflow::<u32,_,_>(move |scope| {
+            let (facts_input, facts) = scope.new_collection();
+            let (dims_input, dims) = scope.new_collection();
+
+            // the skewed join must agree with the plain join, however keys are classified.
+            facts.join_skewed(&dims, 20, 2)
+                 .concat(&facts.join(&dims).negate())
+                 .consolidate()
+                 .inspect(move |x| shared.lock().unwrap().push(x.clone()));
+
+            (facts_input, dims_input)
+        });
+
+        // key zero starts heavy, and becomes light once most of its records are retracted.
+        for round in 0 .. 3u32 {
+            for record in 0 .. 30u32 {
+                if record % 3 == index as u32 {
+                    facts.update((round % 2 * record % 5, record), if round == 2 { -1 } else { 1 });
+                }
+            }
+            if index == 0 {
+                dims.insert((round, 'a' as u32 + round));
+                dims.insert((0, round));
+            }
+            facts.advance_to(round + 1);
+            dims.advance_to(round + 1);
+        }
+    }).unwrap();
+
+    assert!(results.lock().unwrap().is_empty());
+}
+
+#[test]
+fn join_skewed_heavy_local() {
+
+    use std::sync::{Arc, Mutex};
+    use timely::Configuration;
+    use differential_dataflow::input::Input;
+    use differential_dataflow::operators::join::JoinSkewed;
+
+    let results = Arc::new(Mutex::new(Vec::new()));
+    let shared = results.clone();
+
+    timely::execute(Configuration::Process(3), move |worker| {
+        let index = worker.index();
+        let shared = shared.clone();
+        worker.dataflow::<u32,_,_>(move |scope| {
+            // key zero is heavy, and each worker holds an equal share of its records.
+            let facts = scope.new_collection_from((0 .. 60u32).filter(move |x| x % 3 == index as u32).map(|x| (if x % 2 == 0 { 0 } else { 1 + x % 5 }, x))).1;
+            let dims = scope.new_collection_from(if index == 0 { (0 .. 6u32).map(|x| (x, x)).collect() } else { vec![] }).1;
+
+            let skewed_shared = shared.clone();
+            facts.join_skewed(&dims, 20, 1)
+                 .inspect(move |x| skewed_shared.lock().unwrap().push((true, index, (x.0).0)));
+            facts.join(&dims)
+                 .inspect(move |x| shared.lock().unwrap().push((false, index, (x.0).0)));
+        });
+    }).unwrap();
+
+    let results = results.lock().unwrap().clone();
+    let workers = |skewed: bool| {
+        let mut workers = results.iter().filter(|x| x.0 == skewed && x.2 == 0).map(|x| x.1).collect::<Vec<_>>();
+        workers.sort();
+        workers.dedup();
+        workers
+    };
+
+    // the join by key matches all heavy records at one worker, and the skewed join where they are spread.
+    assert_eq!(results.iter().filter(|x| x.0 && x.2 == 0).count(), 30);
+    assert_eq!(workers(false).len(), 1);
+    assert_eq!(workers(true), vec![0, 1, 2]);
+}
+
+#[test]
+fn join_broadcast() {
+
+    use std::sync::{Arc, Mutex};
+    use timely::Configuration;
+    use differential_dataflow::input::Input;
+
+    let results = Arc::new(Mutex::new(Vec::new()));
+    let shared = results.clone();
+
+    timely::execute(Configuration::Process(2), move |worker| {
+        let index = worker.index();
+        let shared = shared.clone();
+        worker.dataflow::<u32,_,_>(move |scope| {
+            // each worker holds its own facts, and only worker zero holds the dimension table.
+            let facts = scope.new_collection_from(vec![(index, index * 10), (1 - index, index * 10 + 1)]).1;
+            let dims = scope.new_collection_from(if index == 0 { vec![(0, 'a'), (1, 'b')] } else { vec![] }).1;
+
+            // facts are joined where they are, rather than at the worker for their key.
+            facts.join_broadcast(&dims)
+                 .inspect(move |x| shared.lock().unwrap().push((index, x.0)));
+        });
+    }).unwrap();
+
+    let mut results = results.lock().unwrap().clone();
+    results.sort();
+    assert_eq!(results, vec![(0, (0, (0, 'a'))), (0, (1, (1, 'b'))), (1, (0, (11, 'a'))), (1, (1, (10, 'b')))]);
+}
+
+#[test]
+fn delta_join_triangles() {
+
+    use differential_dataflow::input::Input;
+    use differential_dataflow::operators::delta::DeltaJoin;
+
+    timely::execute(timely::Configuration::Thread, move |worker| {
+
+        let mut input = worker.dataflow::<u32,_,_>(|scope| {
+
+            let (input, edges) = scope.new_collection::<(u32, u32), isize>();
+            let rows = edges.map(|(x, y)| vec![x, y]);
+
+            // triangles(a,b,c) := edges(a,b), edges(b,c), edges(a,c)
+            let delta =
+            DeltaJoin::new()
+                .relation(&rows, &[0, 1])
+                .relation(&rows, &[1, 2])
+                .relation(&rows, &[0, 2])
+                .join()
+                .expect("well-formed join");
+
+            let expected =
+            edges.map(|(a, b)| (b, a))
+                 .join_map(&edges, |&b, &a, &c| ((a, c), b))
+                 .semijoin(&edges)
+                 .map(|((a, c), b)| vec![a, b, c]);
+
+            delta.assert_eq(&expected);
+            input
+        });
+
+        // several edges change at once, so that changes to different relations coincide.
+        input.insert((0, 1));
+        input.insert((1, 2));
+        input.insert((0, 2));
+        input.insert((2, 3));
+        input.advance_to(1);
+        input.insert((1, 3));
+        input.insert((0, 3));
+        input.advance_to(2);
+        input.remove((1, 2));
+        input.insert((2, 1));
+        input.insert((0, 1));
+        input.advance_to(3);
+        input.remove((0, 1));
+        input.remove((0, 1));
+        input.insert((1, 0));
+    }).unwrap();
+}
+
+#[test]
+fn delta_join_arranged() {
+
+    use differential_dataflow::input::Input;
+    use differential_dataflow::operators::arrange::ArrangeByKey;
+    use differential_dataflow::operators::delta::DeltaJoin;
+
+    timely::execute(timely::Configuration::Thread, move |worker| {
+
+        let mut input = worker.dataflow::<u32,_,_>(|scope| {
+
+            let (input, edges) = scope.new_collection::<(u32, u32), isize>();
+            let rows = edges.map(|(x, y)| vec![x, y]);
+            let forward = edges.map(|(x, y)| (vec![x], vec![y])).arrange_by_key();
+
+            // triangles(a,b,c) := edges(a,b), edges(b,c), edges(a,c), with each relation arranged by source.
+            let delta =
+            DeltaJoin::new()
+                .relation_arranged(&forward, &[0], &[0, 1])
+                .relation_arranged(&forward, &[0], &[1, 2])
+                .relation_arranged(&forward, &[0], &[0, 2])
+                .join()
+                .expect("well-formed join");
+
+            let expected =
+            DeltaJoin::new()
+                .relation(&rows, &[0, 1])
+                .relation(&rows, &[1, 2])
+                .relation(&rows, &[0, 2])
+                .join()
+                .expect("well-formed join");
+
+            delta.assert_eq(&expected);
+            input
+        });
+
+        input.insert((0, 1));
+        input.insert((1, 2));
+        input.insert((0, 2));
+        input.advance_to(1);
+        input.insert((2, 3));
+        input.insert((1, 3));
+        input.advance_to(2);
+        input.remove((1, 2));
+    }).unwrap();
+}
+
+#[test]
+fn delta_join_arranged_key_columns() {
+
+    use differential_dataflow::input::Input;
+    use differential_dataflow::operators::arrange::ArrangeByKey;
+    use differential_dataflow::operators::delta::DeltaJoin;
+
+    let error = timely::example(|scope| {
+        let arranged = scope.new_collection_from(vec![(vec![0u32], vec![1u32])]).1.arrange_by_key();
+        DeltaJoin::new()
+            .relation_arranged(&arranged, &[2], &[0, 1])
+            .join()
+            .err()
+    });
+
+    assert!(error.expect("join should be rejected").contains("not strictly increasing columns"));
+}
+
+#[test]
+fn delta_join_disconnected() {
+
+    use differential_dataflow::input::Input;
+    use differential_dataflow::operators::delta::DeltaJoin;
+
+    let error = timely::example(|scope| {
+        let rows = scope.new_collection_from(vec![vec![0u32, 1]]).1;
+        DeltaJoin::new()
+            .relation(&rows, &[0, 1])
+            .relation(&rows, &[2, 3])
+            .join()
+            .err()
+    });
+
+    assert!(error.expect("join should be rejected").contains("share no variables"));
+}
+
+#[test]
+fn half_join_as_of_time() {
+
+    use timely::dataflow::operators::{Inspect, Probe};
+    use differential_dataflow::input::Input;
+    use differential_dataflow::operators::arrange::ArrangeByKey;
+    use differential_dataflow::operators::half_join::{half_join, ProbeAs};
+
+    let results = ::std::sync::Arc::new(::std::sync::Mutex::new(Vec::new()));
+    let shared = results.clone();
+
+    timely::execute(timely::Configuration::Thread, move |worker| {
+
+        let shared = shared.clone();
+        let (mut stream, mut table, probe) = worker.dataflow::<u32,_,_>(|scope| {
+            let (stream_input, stream) = scope.new_collection::<(u32, u32), isize>();
+            let (table_input, table) = scope.new_collection::<(u32, char), isize>();
+            let probe =
+            half_join(&stream, table.arrange_by_key(), ProbeAs::Time, 1, |_key, &val, &name| (val, name))
+                .inner
+                .inspect(move |x| shared.lock().unwrap().push(x.clone()))
+                .probe();
+            (stream_input, table_input, probe)
+        });
+
+        // the table changes after the first probe, which should not see the change.
+        table.insert((0, 'a'));
+        stream.insert((0, 10));
+        stream.insert((0, 11));
+        stream.advance_to(1); stream.flush();
+        table.advance_to(1); table.flush();
+        table.remove((0, 'a'));
+        table.insert((0, 'b'));
+        stream.insert((0, 12));
+        stream.advance_to(2); stream.flush();
+        table.advance_to(2); table.flush();
+        while probe.less_than(stream.time()) { worker.step(); }
+    }).unwrap();
+
+    let mut results = results.lock().unwrap().clone();
+    results.sort();
+    assert_eq!(results, vec![((10, 'a'), 0, 1), ((11, 'a'), 0, 1), ((12, 'b'), 1, 1)]);
+}
+
+#[test]
+fn half_join_as_of_frontier() {
+
+    use timely::dataflow::operators::{Inspect, Probe};
+    use differential_dataflow::input::Input;
+    use differential_dataflow::operators::arrange::ArrangeByKey;
+    use differential_dataflow::operators::half_join::{half_join, ProbeAs};
+
+    let results = ::std::sync::Arc::new(::std::sync::Mutex::new(Vec::new()));
+    let shared = results.clone();
+
+    timely::execute(timely::Configuration::Thread, move |worker| {
+
+        let shared = shared.clone();
+        let output = shared.clone();
+        let (mut stream, mut table, table_probe) = worker.dataflow::<u32,_,_>(|scope| {
+            let (stream_input, stream) = scope.new_collection::<(u32, u32), isize>();
+            let (table_input, table) = scope.new_collection::<(u32, char), isize>();
+            let table = table.arrange_by_key();
+            let table_probe = table.stream.probe();
+            half_join(&stream, table, ProbeAs::Frontier, 1, |_key, &val, &name| (val, name))
+                .inner
+                .inspect(move |x| shared.lock().unwrap().push(x.clone()));
+            (stream_input, table_input, table_probe)
+        });
+
+        // both updates are at time zero, but each sees the table as it was when the update arrived.
+        table.insert((0, 'a'));
+        table.advance_to(1); table.flush();
+        while table_probe.less_than(table.time()) { worker.step(); }
+        stream.insert((0, 10));
+        stream.flush();
+        while output.lock().unwrap().len() < 1 { worker.step(); }
+        table.remove((0, 'a'));
+        table.insert((0, 'b'));
+        table.advance_to(2); table.flush();
+        while table_probe.less_than(table.time()) { worker.step(); }
+        stream.insert((0, 11));
+        stream.flush();
+        while output.lock().unwrap().len() < 2 { worker.step(); }
+    }).unwrap();
+
+    let mut results = results.lock().unwrap().clone();
+    results.sort();
+    assert_eq!(results, vec![((10, 'a'), 0, 1), ((11, 'b'), 0, 1)]);
+}
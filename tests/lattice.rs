@@ -0,0 +1,170 @@
+extern crate rand;
+extern crate timely;
+extern crate differential_dataflow;
+
+use rand::{Rng, SeedableRng, StdRng};
+
+use timely::order::{PartialOrder, Product};
+use timely::progress::Antichain;
+
+use differential_dataflow::lattice::{Lattice, Bitemporal, Lexicographic, Total, advance_and_consolidate, advance_and_consolidate_data};
+
+type Time = Product<u64, u64>;
+
+/// The meet of the joins of `time` with each frontier element, without fast paths.
+fn reference_advance(time: &Time, frontier: &[Time]) -> Time {
+    let mut result = time.join(&frontier[0]);
+    for f in &frontier[1..] {
+        result = result.meet(&time.join(f));
+    }
+    result
+}
+
+fn random_frontier(rng: &mut StdRng, bound: u64) -> Antichain<Time> {
+    let mut frontier = Antichain::new();
+    for _ in 0 .. rng.gen_range(1, 4) {
+        frontier.insert(Product::new(rng.gen_range(0, bound), rng.gen_range(0, bound)));
+    }
+    frontier
+}
+
+fn accumulate(updates: &[(Time, isize)], time: &Time) -> isize {
+    updates.iter().filter(|x| x.0.less_equal(time)).map(|x| x.1).sum()
+}
+
+#[test]
+fn advance_by_matches_reference() {
+
+    let seed: &[_] = &[1, 2, 3, 4];
+    let mut rng: StdRng = SeedableRng::from_seed(seed);
+
+    for _ in 0 .. 1000 {
+        let frontier = random_frontier(&mut rng, 8);
+        let time = Product::new(rng.gen_range(0, 10), rng.gen_range(0, 10));
+        let mut advanced = time.clone();
+        advanced.advance_by(frontier.elements());
+        assert_eq!(advanced, reference_advance(&time, frontier.elements()));
+    }
+}
+
+#[test]
+fn compaction_preserves_accumulations() {
+
+    let seed: &[_] = &[5, 6, 7, 8];
+    let mut rng: StdRng = SeedableRng::from_seed(seed);
+
+    for _ in 0 .. 200 {
+
+        let frontier = random_frontier(&mut rng, 6);
+        let original =
+        (0 .. rng.gen_range(0, 20))
+            .map(|_| (Product::new(rng.gen_range(0, 8), rng.gen_range(0, 8)), rng.gen_range(-2, 3)))
+            .collect::<Vec<_>>();
+
+        let mut compacted = original.clone();
+        let count = advance_and_consolidate(&mut compacted[..], frontier.elements());
+        compacted.truncate(count);
+
+        // consolidated updates are sorted, distinct, and non-zero.
+        assert!(compacted.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(compacted.iter().all(|x| x.1 != 0));
+
+        for outer in 0 .. 10 {
+            for inner in 0 .. 10 {
+                let time = Product::new(outer, inner);
+                if frontier.less_equal(&time) {
+                    assert_eq!(accumulate(&original, &time), accumulate(&compacted, &time));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn compaction_with_data() {
+
+    let seed: &[_] = &[9, 10, 11, 12];
+    let mut rng: StdRng = SeedableRng::from_seed(seed);
+
+    for _ in 0 .. 200 {
+
+        let frontier = random_frontier(&mut rng, 6);
+        let original =
+        (0 .. rng.gen_range(0, 20))
+            .map(|_| ((rng.gen_range(0, 3), Product::new(rng.gen_range(0, 8), rng.gen_range(0, 8))), rng.gen_range(-2, 3)))
+            .collect::<Vec<((u32, Time), isize)>>();
+
+        let mut compacted = original.clone();
+        advance_and_consolidate_data(&mut compacted, frontier.elements());
+
+        // each datum accumulates as before, and as when compacted without data.
+        for datum in 0 .. 3 {
+            let restrict = |updates: &[((u32, Time), isize)]| updates.iter().filter(|x| (x.0).0 == datum).map(|x| ((x.0).1.clone(), x.1)).collect::<Vec<_>>();
+            let mut expected = restrict(&original);
+            let count = advance_and_consolidate(&mut expected[..], frontier.elements());
+            expected.truncate(count);
+            assert_eq!(restrict(&compacted), expected);
+        }
+    }
+}
+
+#[test]
+fn bitemporal_advance_by() {
+
+    let frontier = [Bitemporal::new(3, 5), Bitemporal::new(4, 2)];
+
+    let mut time = Bitemporal::new(1, 1);
+    time.advance_by(&frontier[..]);
+    assert_eq!(time, Bitemporal::new(3, 2));
+
+    let mut time = Bitemporal::new(6, 4);
+    time.advance_by(&frontier[..]);
+    assert_eq!(time, Bitemporal::new(6, 4));
+}
+
+#[test]
+fn lexicographic_lattice() {
+
+    let seed: &[_] = &[9, 10, 11, 12];
+    let mut rng: StdRng = SeedableRng::from_seed(seed);
+
+    let mut times = Vec::new();
+    for _ in 0 .. 30 {
+        let time = Product::new(rng.gen_range(0, 3u64), rng.gen_range(0, 3u64));
+        times.push(Lexicographic::new(time, rng.gen_range(0, 3u8)));
+    }
+
+    // joins and meets are the least upper and greatest lower bounds among the sampled times.
+    for a in times.iter() {
+        for b in times.iter() {
+            let join = a.join(b);
+            let meet = a.meet(b);
+            assert!(a.less_equal(&join) && b.less_equal(&join));
+            assert!(meet.less_equal(a) && meet.less_equal(b));
+            for c in times.iter() {
+                if a.less_equal(c) && b.less_equal(c) { assert!(join.less_equal(c)); }
+                if c.less_equal(a) && c.less_equal(b) { assert!(c.less_equal(&meet)); }
+            }
+        }
+    }
+}
+
+#[test]
+fn total_lattice() {
+
+    let times = vec![Total(None), Total(Some((0u64, 1u32))), Total(Some((1, 0))), Total(Some((1, 2)))];
+
+    // totally ordered times join to the greater and meet to the lesser, starting from the default.
+    assert_eq!(<Total<Option<(u64, u32)>> as Lattice>::minimum(), times[0]);
+    for (i, a) in times.iter().enumerate() {
+        for (j, b) in times.iter().enumerate() {
+            assert_eq!(a.less_equal(b), i <= j);
+            assert_eq!(a.join(b), times[::std::cmp::max(i, j)]);
+            assert_eq!(a.meet(b), times[::std::cmp::min(i, j)]);
+        }
+    }
+
+    let mut time = Total(Some((0, 5)));
+    time.advance_by(&[Total(Some((0, 7))), Total(Some((1, 0)))]);
+    assert_eq!(time, Total(Some((0, 7))));
+}
@@ -0,0 +1,45 @@
+extern crate rand;
+extern crate timely;
+extern crate differential_dataflow;
+
+mod common;
+
+use std::collections::HashSet;
+
+use timely::Configuration;
+
+use differential_dataflow::algorithms::graphs::motifs;
+
+use common::{Edge, random_changes, from_scratch, differential};
+
+/// The number of triangles in the undirected graph of `edges`, present only if positive.
+fn triangles(edges: &HashSet<Edge>) -> Vec<(((), isize), isize)> {
+    let undirected = edges.iter().filter(|e| e.0 != e.1).map(|&(x, y)| if x < y { (x, y) } else { (y, x) }).collect::<HashSet<_>>();
+    let count = undirected.iter().flat_map(|&(a, b)| undirected.iter().map(move |&(c, d)| (a, b, c, d)))
+                          .filter(|&(a, b, c, d)| a == c && b < d && undirected.contains(&(b, d)))
+                          .count() as isize;
+    if count > 0 { vec![(((), count), 1)] } else { vec![] }
+}
+
+/// The number of simple directed paths of two edges, present only if positive.
+fn paths(edges: &HashSet<Edge>) -> Vec<(((), isize), isize)> {
+    let count = edges.iter().flat_map(|&(a, b)| edges.iter().map(move |&(c, d)| (a, b, c, d)))
+                     .filter(|&(a, b, c, d)| b == c && a != b && b != d && a != d)
+                     .count() as isize;
+    if count > 0 { vec![(((), count), 1)] } else { vec![] }
+}
+
+#[test]
+fn motifs_match_sequential() {
+
+    let changes = random_changes(10, 12, 40, 5, 3);
+
+    let expected_triangles = from_scratch(&changes, triangles);
+    let expected_paths = from_scratch(&changes, paths);
+
+    let configs: [fn()->Configuration; 2] = [|| Configuration::Thread, || Configuration::Process(2)];
+    for config in configs.iter() {
+        assert_eq!(differential(&changes, &[], config(), |edges, _| motifs::count_total(&motifs::triangles(edges))), expected_triangles);
+        assert_eq!(differential(&changes, &[], config(), |edges, _| motifs::count_total(&motifs::paths(edges, 2))), expected_paths);
+    }
+}
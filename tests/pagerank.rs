@@ -0,0 +1,62 @@
+extern crate rand;
+extern crate timely;
+extern crate differential_dataflow;
+
+mod common;
+
+use std::collections::{HashMap, HashSet};
+
+use timely::Configuration;
+
+use differential_dataflow::algorithms::graphs::pagerank::{pagerank, personalized_pagerank, SURFERS};
+
+use common::{Node, Edge, random_changes, from_scratch, differential};
+
+const ITERATIONS: u64 = 5;
+const DAMPING: f64 = 0.85;
+
+/// The rank of each node, as its multiplicity, with surfers starting at `sources` or at every node.
+fn ranks(edges: &HashSet<Edge>, sources: Option<&[Node]>, iterations: u64) -> Vec<(Node, isize)> {
+
+    let follow = (DAMPING * 1_000_000.0) as isize;
+    let resume = SURFERS - SURFERS * follow / 1_000_000;
+
+    let starts = sources.map(|x| x.to_vec()).unwrap_or_else(|| {
+        let nodes = edges.iter().flat_map(|&(src, dst)| vec![src, dst]).collect::<HashSet<_>>();
+        nodes.into_iter().collect()
+    });
+    let mut degrees = HashMap::new();
+    for &(src, _) in edges.iter() {
+        *degrees.entry(src).or_insert(0) += 1;
+    }
+
+    let mut ranks = starts.iter().map(|&node| (node, SURFERS)).collect::<HashMap<_,_>>();
+    for _ in 0 .. iterations {
+        let mut next = starts.iter().map(|&node| (node, resume)).collect::<HashMap<_,_>>();
+        for &(src, dst) in edges.iter() {
+            let surfers = ranks.get(&src).cloned().unwrap_or(0);
+            *next.entry(dst).or_insert(0) += (surfers as i128 * follow as i128 / 1_000_000) as isize / degrees[&src];
+        }
+        ranks = next;
+    }
+    ranks.into_iter().collect()
+}
+
+#[test]
+fn pagerank_matches_sequential() {
+
+    let changes = random_changes(5, 20, 50, 5, 3);
+
+    for &iterations in [0, 1, ITERATIONS].iter() {
+        let expected = from_scratch(&changes, |edges| ranks(edges, None, iterations));
+        let results = differential(&changes, &[], Configuration::Process(2), move |edges, _| pagerank(edges, iterations, DAMPING));
+        assert_eq!(results, expected);
+    }
+
+    let sources = [0, 1];
+    for &iterations in [0, ITERATIONS].iter() {
+        let expected = from_scratch(&changes, |edges| ranks(edges, Some(&sources), iterations));
+        let results = differential(&changes, &sources, Configuration::Thread, move |edges, roots| personalized_pagerank(edges, roots, iterations, DAMPING));
+        assert_eq!(results, expected);
+    }
+}
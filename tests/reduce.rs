@@ -42,4 +42,70 @@ fn reduce_scaling() {
 
     let extracted = data.extract();
     assert_eq!(extracted.len(), 1);
-}
\ No newline at end of file
+}
+
+#[test]
+fn reduce_semigroup() {
+
+    use differential_dataflow::difference::MinPlus;
+    use differential_dataflow::operators::reduce::ReduceCore;
+    use differential_dataflow::trace::implementations::ord::OrdValSpine;
+
+    let data = timely::example(|scope| {
+
+        // the minimum only decreases as values arrive.
+        let col1 = vec![((0,5), 0, 1),((0,3), 1, 1),((0,4), 2, 1),((1,2), 2, 1)]
+                        .into_iter()
+                        .to_stream(scope)
+                        .as_collection();
+
+        col1.reduce_semigroup::<_,OrdValSpine<_,_,_,_>>("Min", |_,s,t| t.push(((), MinPlus::new(*s[0].0))))
+            .as_collection(|k,_| *k)
+            .inner
+            .capture()
+    });
+
+    let mut extracted = data.extract().into_iter().flat_map(|(_, data)| data).collect::<Vec<_>>();
+    extracted.sort();
+    assert_eq!(extracted, vec![(0, 0, MinPlus::new(5)), (0, 1, MinPlus::new(3)), (1, 2, MinPlus::new(2))]);
+}
+
+#[test]
+#[should_panic(expected = "without negation")]
+fn reduce_semigroup_retraction() {
+
+    use differential_dataflow::difference::MinPlus;
+    use differential_dataflow::operators::reduce::ReduceCore;
+    use differential_dataflow::trace::implementations::ord::OrdValSpine;
+
+    timely::example(|scope| {
+
+        // the maximum increases as values arrive, which a minimum cannot express without negation.
+        let col1 = vec![((0,3), 0, 1),((0,5), 1, 1)]
+                        .into_iter()
+                        .to_stream(scope)
+                        .as_collection();
+
+        col1.reduce_semigroup::<_,OrdValSpine<_,_,_,_>>("Max", |_,s,t| t.push(((), MinPlus::new(*s[s.len()-1].0))));
+    });
+}
+
+#[test]
+#[should_panic(expected = "append-only")]
+fn reduce_semigroup_input_retraction() {
+
+    use differential_dataflow::difference::MinPlus;
+    use differential_dataflow::operators::reduce::ReduceCore;
+    use differential_dataflow::trace::implementations::ord::OrdValSpine;
+
+    timely::example(|scope| {
+
+        // retracting the minimum would increase it, and is rejected as it arrives.
+        let col1 = vec![((0,5), 0, 1),((0,3), 0, 1),((0,3), 1, -1)]
+                        .into_iter()
+                        .to_stream(scope)
+                        .as_collection();
+
+        col1.reduce_semigroup::<_,OrdValSpine<_,_,_,_>>("Min", |_,s,t| t.push(((), MinPlus::new(*s[0].0))));
+    });
+}
@@ -0,0 +1,45 @@
+extern crate rand;
+extern crate timely;
+extern crate differential_dataflow;
+
+mod common;
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use timely::Configuration;
+
+use differential_dataflow::algorithms::graphs::sequential::{color, independent_set};
+
+use common::{Node, Edge, random_changes, from_scratch, differential};
+
+/// The colors and independent set members of `edges`, chosen greedily in order of node.
+fn greedy(edges: &HashSet<Edge>) -> (BTreeMap<Node, u32>, BTreeSet<Node>) {
+
+    let mut neighbors = BTreeMap::new();
+    for &(src, dst) in edges.iter().filter(|e| e.0 != e.1) {
+        neighbors.entry(src).or_insert(BTreeSet::new()).insert(dst);
+        neighbors.entry(dst).or_insert(BTreeSet::new()).insert(src);
+    }
+
+    // visit nodes in order, so that smaller neighbors are decided first.
+    let mut colors = BTreeMap::new();
+    let mut members = BTreeSet::new();
+    for (&node, adjacent) in neighbors.iter() {
+        let used = adjacent.iter().filter_map(|n| colors.get(n)).cloned().collect::<BTreeSet<u32>>();
+        colors.insert(node, (1 ..).find(|c| !used.contains(c)).unwrap());
+        if !adjacent.iter().any(|n| members.contains(n)) { members.insert(node); }
+    }
+    (colors, members)
+}
+
+#[test]
+fn sequential_match_from_scratch() {
+
+    let changes = random_changes(8, 20, 30, 3, 3);
+
+    let expected_colors = from_scratch(&changes, |edges| greedy(edges).0.into_iter().map(|x| (x, 1)));
+    let expected_members = from_scratch(&changes, |edges| greedy(edges).1.into_iter().map(|x| (x, 1)));
+
+    assert_eq!(differential(&changes, &[], Configuration::Process(2), |edges, _| color(edges)), expected_colors);
+    assert_eq!(differential(&changes, &[], Configuration::Process(2), |edges, _| independent_set(edges)), expected_members);
+}
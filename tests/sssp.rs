@@ -0,0 +1,116 @@
+extern crate rand;
+extern crate timely;
+extern crate differential_dataflow;
+
+use rand::{Rng, SeedableRng, StdRng};
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use timely::Configuration;
+use timely::dataflow::operators::Capture;
+use timely::dataflow::operators::capture::Extract;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::operators::Consolidate;
+use differential_dataflow::algorithms::graphs::sssp::sssp;
+
+type Node = usize;
+type Edge = (Node, Node, u64);
+
+#[test] fn sssp_10_20_100() { test_sizes(10, 20, 100, Configuration::Process(3)); }
+#[test] fn sssp_100_200_10() { test_sizes(100, 200, 10, Configuration::Process(3)); }
+#[test] fn sssp_100_2000_1() { test_sizes(100, 2000, 1, Configuration::Thread); }
+
+fn test_sizes(nodes: usize, edges: usize, rounds: usize, config: Configuration) {
+
+    let seed: &[_] = &[1, 2, 3, 4];
+    let mut rng1: StdRng = SeedableRng::from_seed(seed);    // rng for edge additions
+    let mut rng2: StdRng = SeedableRng::from_seed(seed);    // rng for edge deletions
+
+    let mut edge_list = Vec::new();
+    for _ in 0 .. edges {
+        edge_list.push(((rng1.gen_range(0, nodes), rng1.gen_range(0, nodes), rng1.gen_range(0, 10)), 0, 1));
+    }
+    for round in 1 .. rounds {
+        edge_list.push(((rng1.gen_range(0, nodes), rng1.gen_range(0, nodes), rng1.gen_range(0, 10)), round, 1));
+        edge_list.push(((rng2.gen_range(0, nodes), rng2.gen_range(0, nodes), rng2.gen_range(0, 10)), round,-1));
+    }
+
+    let mut results1 = sssp_sequential(0, &edge_list, rounds);
+    let mut results2 = sssp_differential(0, edge_list, config);
+    results1.sort();
+    results2.sort();
+    assert_eq!(results1, results2);
+}
+
+/// Changes to (node, dist) pairs in each round, from Bellman-Ford on each round's edges.
+fn sssp_sequential(root: Node, edge_list: &[(Edge, usize, isize)], rounds: usize) -> Vec<((Node, u64), usize, isize)> {
+
+    let mut results = Vec::new();
+    let mut prior = HashMap::new();
+    for round in 0 .. rounds {
+
+        let mut edges = HashMap::new();
+        for &(edge, time, diff) in edge_list.iter() {
+            if time <= round { *edges.entry(edge).or_insert(0) += diff; }
+        }
+
+        let mut dists = HashMap::new();
+        dists.insert(root, 0);
+        let mut changes = true;
+        while changes {
+            changes = false;
+            for (&(src, dst, weight), &count) in edges.iter() {
+                if count > 0 {
+                    if let Some(&dist) = dists.get(&src) {
+                        if dists.get(&dst).map(|&d| d > dist + weight).unwrap_or(true) {
+                            dists.insert(dst, dist + weight);
+                            changes = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        for (&node, &dist) in dists.iter() {
+            if prior.get(&node) != Some(&dist) { results.push(((node, dist), round, 1)); }
+        }
+        for (&node, &dist) in prior.iter() {
+            if dists.get(&node) != Some(&dist) { results.push(((node, dist), round, -1)); }
+        }
+        prior = dists;
+    }
+    results
+}
+
+fn sssp_differential(root: Node, edge_list: Vec<(Edge, usize, isize)>, config: Configuration) -> Vec<((Node, u64), usize, isize)> {
+
+    let (send, recv) = ::std::sync::mpsc::channel();
+    let send = Arc::new(Mutex::new(send));
+
+    timely::execute(config, move |worker| {
+
+        let send = send.lock().unwrap().clone();
+        let (mut roots, mut edges) = worker.dataflow::<usize,_,_>(|scope| {
+            let (root_input, roots) = scope.new_collection();
+            let (edge_input, edges) = scope.new_collection();
+            sssp(&edges, &roots).consolidate().inner.capture_into(send);
+            (root_input, edge_input)
+        });
+
+        if worker.index() == 0 {
+            roots.insert(root);
+            for &(edge, time, diff) in edge_list.iter() {
+                edges.advance_to(time);
+                edges.update(edge, diff);
+            }
+        }
+
+    }).unwrap();
+
+    recv.extract()
+        .into_iter()
+        .flat_map(|(_, list)| list.into_iter())
+        .collect()
+}
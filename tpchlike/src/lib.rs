@@ -2,6 +2,8 @@
 extern crate abomonation;
 extern crate timely;
 extern crate differential_dataflow;
+#[macro_use]
+extern crate differential_derive;
 extern crate arrayvec;
 extern crate regex;
 
@@ -3,7 +3,6 @@ use timely::dataflow::*;
 use timely::dataflow::operators::probe::Handle as ProbeHandle;
 
 use differential_dataflow::operators::*;
-use differential_dataflow::difference::DiffPair;
 use differential_dataflow::lattice::Lattice;
 
 use {Arrangements, Experiment, Collections};
@@ -37,6 +36,30 @@ use {Arrangements, Experiment, Collections};
 //     l_linestatus;
 // :n -1
 
+/// The sums accumulated for each group; averages follow by dividing by `count`.
+#[derive(Ord,PartialOrd,Eq,PartialEq,Clone,Copy,Debug,Semigroup,Monoid,Abelian,Mul)]
+pub struct Aggregates {
+    pub quantity: isize,
+    pub base_price: isize,
+    pub disc_price: isize,
+    pub charge: isize,
+    pub discount: isize,
+    pub count: isize,
+}
+
+unsafe_abomonate!(Aggregates);
+
+fn aggregates(item: &::types::LineItem) -> Aggregates {
+    Aggregates {
+        quantity: item.quantity as isize,
+        base_price: item.extended_price as isize,
+        disc_price: (item.extended_price * (100 - item.discount) / 100) as isize,
+        charge: (item.extended_price * (100 - item.discount) * (100 + item.tax) / 10000) as isize,
+        discount: item.discount as isize,
+        count: 1,
+    }
+}
+
 pub fn query<G: Scope>(collections: &mut Collections<G>, probe: &mut ProbeHandle<G::Timestamp>)
 where
     G::Timestamp: Lattice+TotalOrder+Ord
@@ -45,12 +68,7 @@ where
         .lineitems()
         .explode(|item|
             if item.ship_date <= ::types::create_date(1998, 9, 2) {
-                Some(((item.return_flag[0], item.line_status[0]),
-                    DiffPair::new(item.quantity as isize,
-                    DiffPair::new(item.extended_price as isize,
-                    DiffPair::new((item.extended_price * (100 - item.discount) / 100) as isize,
-                    DiffPair::new((item.extended_price * (100 - item.discount) * (100 + item.tax) / 10000) as isize,
-                    DiffPair::new(item.discount as isize, 1)))))))
+                Some(((item.return_flag[0], item.line_status[0]), aggregates(&item)))
             }
             else {
                 None
@@ -74,12 +92,7 @@ where
         .lineitem(scope)
         .explode(|item|
             if item.ship_date <= ::types::create_date(1998, 9, 2) {
-                Some(((item.return_flag[0], item.line_status[0]),
-                    DiffPair::new(item.quantity as isize,
-                    DiffPair::new(item.extended_price as isize,
-                    DiffPair::new((item.extended_price * (100 - item.discount) / 100) as isize,
-                    DiffPair::new((item.extended_price * (100 - item.discount) * (100 + item.tax) / 10000) as isize,
-                    DiffPair::new(item.discount as isize, 1)))))))
+                Some(((item.return_flag[0], item.line_status[0]), aggregates(&item)))
             }
             else {
                 None